@@ -1,4 +1,4 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
 use scene_graph::*;
 
 pub fn criterion_benchmark(c: &mut Criterion) {
@@ -12,7 +12,7 @@ pub fn criterion_benchmark(c: &mut Criterion) {
     group.bench_function("scene-graph", |b| {
         b.iter(|| {
             let idx = sg.attach_at_root("single boy");
-            sg.remove(idx);
+            sg.remove(idx).unwrap();
         })
     });
     group.bench_function("petgraph", |b| {
@@ -38,7 +38,7 @@ pub fn criterion_benchmark(c: &mut Criterion) {
     group.bench_function("scene-graph", |b| {
         b.iter(|| {
             let idx = sg.attach_at_root("Finality");
-            sg.remove(idx);
+            sg.remove(idx).unwrap();
         })
     });
     group.bench_function("petgraph", |b| {
@@ -74,12 +74,8 @@ pub fn criterion_benchmark(c: &mut Criterion) {
     });
     group.bench_function("petgraph", |b| {
         b.iter(|| {
-            petgraph::visit::depth_first_search(&petgraph_sg, Some(root_idx), |event| match event {
-                petgraph::visit::DfsEvent::Discover(_, _) => todo!(),
-                petgraph::visit::DfsEvent::TreeEdge(_, _) => todo!(),
-                petgraph::visit::DfsEvent::BackEdge(_, _) => todo!(),
-                petgraph::visit::DfsEvent::CrossForwardEdge(_, _) => todo!(),
-                petgraph::visit::DfsEvent::Finish(_, _) => todo!(),
+            petgraph::visit::depth_first_search(&petgraph_sg, Some(root_idx), |event| {
+                black_box(event);
             });
         })
     });
@@ -107,6 +103,14 @@ pub fn criterion_benchmark(c: &mut Criterion) {
             }
         })
     });
+    let mut scratch = TraversalScratch::new();
+    group.bench_function("scene-graph with buffer", |b| {
+        b.iter(|| {
+            for v in sg.iter_with_buffer(&mut scratch) {
+                black_box(v);
+            }
+        })
+    });
     group.bench_function("petgraph", |b| {
         b.iter(|| {
             petgraph::visit::depth_first_search(&petgraph_sg, Some(root_idx), |event| {
@@ -116,6 +120,45 @@ pub fn criterion_benchmark(c: &mut Criterion) {
     });
 
     group.finish();
+
+    let mut group = c.benchmark_group("attach 64 children");
+    group.bench_function("attach", |b| {
+        b.iter(|| {
+            let mut sg = SceneGraph::new("Root");
+            for v in input_node.iter().take(64) {
+                sg.attach_at_root(v.as_str());
+            }
+
+            black_box(sg)
+        })
+    });
+    group.bench_function("attach_many", |b| {
+        b.iter(|| {
+            let mut sg = SceneGraph::new("Root");
+            sg.attach_many(NodeIndex::Root, input_node.iter().take(64).map(|v| v.as_str())).unwrap();
+
+            black_box(sg)
+        })
+    });
+    group.finish();
+
+    // a 50k node subtree, 500 wide and two deep, next to a sibling which stays behind
+    let mut sg = SceneGraph::new("Root");
+    let subtree = sg.attach_at_root("Subtree");
+    sg.attach_at_root("Sibling");
+    for chunk in input_node.chunks(100) {
+        let idx = sg.attach(subtree, chunk[0].as_str()).unwrap();
+        sg.attach_many(idx, chunk[1..].iter().map(|v| v.as_str())).unwrap();
+    }
+
+    let mut group = c.benchmark_group("split 50k subtree");
+    group.bench_function("detach", |b| {
+        b.iter_batched(|| sg.clone(), |mut sg| sg.detach(subtree), BatchSize::LargeInput)
+    });
+    group.bench_function("split_off", |b| {
+        b.iter_batched(|| sg.clone(), |mut sg| sg.split_off(subtree), BatchSize::LargeInput)
+    });
+    group.finish();
 }
 
 criterion_group!(benches, criterion_benchmark);
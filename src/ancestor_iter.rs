@@ -0,0 +1,87 @@
+use crate::{NodeIndex, SceneGraph};
+
+/// An iterator over the ancestors of a node in a [SceneGraph], walking upwards.
+/// See [iter_ancestors] for more information.
+///
+/// [iter_ancestors]: SceneGraph::iter_ancestors
+pub struct SceneGraphAncestorIter<'a, T> {
+    sg: &'a SceneGraph<T>,
+    next_ancestor: Option<NodeIndex>,
+}
+
+impl<'a, T> SceneGraphAncestorIter<'a, T> {
+    pub(crate) fn new(sg: &'a SceneGraph<T>, node_index: NodeIndex) -> Self {
+        let next_ancestor = match node_index {
+            NodeIndex::Root => None,
+            NodeIndex::Branch(idx) => Some(sg.arena[idx].parent),
+        };
+
+        SceneGraphAncestorIter { sg, next_ancestor }
+    }
+}
+
+impl<'a, T> Iterator for SceneGraphAncestorIter<'a, T> {
+    type Item = (NodeIndex, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ancestor = self.next_ancestor?;
+
+        match ancestor {
+            NodeIndex::Root => {
+                self.next_ancestor = None;
+
+                Some((NodeIndex::Root, &self.sg.root))
+            }
+            NodeIndex::Branch(idx) => {
+                let node = &self.sg.arena[idx];
+                self.next_ancestor = Some(node.parent);
+
+                Some((ancestor, &node.value))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn root_has_no_ancestors() {
+        let mut sg = SceneGraph::new("Root");
+        sg.attach_at_root("First Child");
+
+        assert!(sg.iter_ancestors(NodeIndex::Root).unwrap().next().is_none());
+    }
+
+    #[test]
+    fn ancestors_end_at_root() {
+        let mut sg = SceneGraph::new("Root");
+        let child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(child, "First Grandchild").unwrap();
+        let great_grandchild = sg.attach(grandchild, "First Great-Grandchild").unwrap();
+
+        assert_eq!(
+            Vec::from_iter(sg.iter_ancestors(great_grandchild).unwrap()),
+            vec![
+                (grandchild, &"First Grandchild"),
+                (child, &"First Child"),
+                (NodeIndex::Root, &"Root")
+            ]
+        );
+
+        assert_eq!(
+            Vec::from_iter(sg.iter_ancestors(child).unwrap()),
+            vec![(NodeIndex::Root, &"Root")]
+        );
+    }
+
+    #[test]
+    fn ancestors_of_removed_node() {
+        let mut sg = SceneGraph::new("Root");
+        let child = sg.attach_at_root("First Child");
+        sg.remove(child).unwrap();
+
+        assert!(sg.iter_ancestors(child).is_err());
+    }
+}
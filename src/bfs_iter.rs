@@ -0,0 +1,138 @@
+use crate::{Children, Node, SceneGraph};
+use alloc::collections::VecDeque;
+
+/// A breadth first iterator over the SceneGraph. See [iter_bfs] for more information.
+///
+/// [iter_bfs]: SceneGraph::iter_bfs
+pub struct SceneGraphBfsIter<'a, T> {
+    sg: &'a SceneGraph<T>,
+    queue: VecDeque<QueueState<'a, T>>,
+}
+
+impl<'a, T> SceneGraphBfsIter<'a, T> {
+    pub(crate) fn new(sg: &'a SceneGraph<T>, root_value: &'a T, root_children: Option<&'a Children>) -> Self {
+        let mut queue = VecDeque::new();
+        if let Some(first_child) = root_children.map(|v| v.first) {
+            queue.push_back(QueueState::new(root_value, &sg.arena[first_child]));
+        };
+        SceneGraphBfsIter { sg, queue }
+    }
+}
+
+impl<'a, T> Iterator for SceneGraphBfsIter<'a, T> {
+    type Item = (&'a T, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let queue_frame = self.queue.pop_front()?;
+
+        // the sibling goes to the front, so we finish this level before descending...
+        if let Some(next_sibling) = queue_frame.current_child.next_sibling {
+            self.queue
+                .push_front(QueueState::new(queue_frame.parent_value, &self.sg.arena[next_sibling]));
+        }
+
+        // ...and the children go to the back, so they're picked up on the next level.
+        if let Some(first_child) = queue_frame.current_child.children.map(|v| v.first) {
+            self.queue.push_back(QueueState::new(
+                &queue_frame.current_child.value,
+                &self.sg.arena[first_child],
+            ));
+        }
+
+        Some((queue_frame.parent_value, &queue_frame.current_child.value))
+    }
+}
+
+#[derive(Debug)]
+struct QueueState<'a, T> {
+    parent_value: &'a T,
+    current_child: &'a Node<T>,
+}
+
+impl<'a, T> QueueState<'a, T> {
+    fn new(parent: &'a T, first_child: &'a Node<T>) -> Self {
+        Self {
+            parent_value: parent,
+            current_child: first_child,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::NodeIndex;
+
+    use super::*;
+
+    #[test]
+    fn scene_graph_returns_nothing_on_empty_iteration() {
+        let scene_graph = SceneGraph::new("Root");
+
+        assert!(scene_graph.iter_bfs().next().is_none());
+    }
+
+    #[test]
+    fn bfs_iteration() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let first_grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach(first_grandchild, "First Great-Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+        sg.attach(second_child, "Second Grandchild").unwrap();
+
+        assert_eq!(
+            Vec::from_iter(sg.iter_bfs().map(|(_parent, value)| value).cloned()),
+            vec![
+                "First Child",
+                "Second Child",
+                "First Grandchild",
+                "Second Grandchild",
+                "First Great-Grandchild"
+            ]
+        );
+
+        assert_eq!(
+            Vec::from_iter(sg.iter().map(|(_parent, value)| value).cloned()),
+            vec![
+                "First Child",
+                "First Grandchild",
+                "First Great-Grandchild",
+                "Second Child",
+                "Second Grandchild"
+            ]
+        );
+    }
+
+    #[test]
+    fn bfs_parents() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach_at_root("Second Child");
+
+        assert_eq!(
+            Vec::from_iter(sg.iter_bfs().map(|(parent, value)| (*parent, *value))),
+            vec![
+                ("Root", "First Child"),
+                ("Root", "Second Child"),
+                ("First Child", "First Grandchild")
+            ]
+        );
+    }
+
+    #[test]
+    fn bfs_from_node() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let first_grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach(first_grandchild, "First Great-Grandchild").unwrap();
+        sg.attach(first_child, "Second Grandchild").unwrap();
+        sg.attach_at_root("Second Child");
+
+        assert_eq!(
+            Vec::from_iter(sg.iter_bfs_from_node(first_child).unwrap().map(|(_parent, value)| value).cloned()),
+            vec!["First Grandchild", "Second Grandchild", "First Great-Grandchild"]
+        );
+        assert!(sg.iter_bfs_from_node(NodeIndex::Root).is_ok());
+    }
+}
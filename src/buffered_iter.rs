@@ -0,0 +1,152 @@
+use alloc::vec::Vec;
+use thunderdome::Index;
+
+use crate::{NodeIndex, SceneGraph};
+
+/// A reusable stack for depth first traversals, so hot loops which iterate every frame don't
+/// allocate a new one each time. See [iter_with_buffer] for more information.
+///
+/// [iter_with_buffer]: SceneGraph::iter_with_buffer
+#[derive(Debug, Default, Clone)]
+pub struct TraversalScratch {
+    stacks: Vec<StackState>,
+}
+
+impl TraversalScratch {
+    /// Creates a new, empty scratch buffer. This doesn't allocate until it's used.
+    pub const fn new() -> Self {
+        Self { stacks: Vec::new() }
+    }
+
+    /// Creates a new scratch buffer with room for `capacity` stack frames, which is roughly the
+    /// depth of the deepest traversal it's used for.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            stacks: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the number of stack frames the buffer can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.stacks.capacity()
+    }
+}
+
+/// An iterator over the SceneGraph which keeps its stack in a caller owned [TraversalScratch].
+/// See [iter_with_buffer] for more information.
+///
+/// [iter_with_buffer]: SceneGraph::iter_with_buffer
+pub struct SceneGraphBufferedIter<'a, T> {
+    sg: &'a SceneGraph<T>,
+    stacks: &'a mut Vec<StackState>,
+}
+
+impl<'a, T> SceneGraphBufferedIter<'a, T> {
+    pub(crate) fn new(sg: &'a SceneGraph<T>, scratch: &'a mut TraversalScratch) -> Self {
+        let stacks = &mut scratch.stacks;
+        stacks.clear();
+
+        if let Some(first_child) = sg.root_children.map(|v| v.first) {
+            stacks.push(StackState::new(NodeIndex::Root, first_child));
+        }
+        SceneGraphBufferedIter { sg, stacks }
+    }
+}
+
+impl<'a, T> Iterator for SceneGraphBufferedIter<'a, T> {
+    type Item = (&'a T, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // if we're out of stack frames, we die here
+        let stack_frame = self.stacks.pop()?;
+        let current_child = &self.sg.arena[stack_frame.current_child];
+
+        // if there's a sibling, push it onto the to do list!
+        if let Some(next_sibling) = current_child.next_sibling {
+            self.stacks.push(StackState::new(stack_frame.parent, next_sibling));
+        }
+
+        if let Some(first_child) = current_child.children.map(|v| v.first) {
+            self.stacks.push(StackState::new(
+                NodeIndex::Branch(stack_frame.current_child),
+                first_child,
+            ));
+        }
+
+        // we only keep indices around, so the buffer isn't tied to any one borrow of the graph
+        let parent_value = match stack_frame.parent {
+            NodeIndex::Root => &self.sg.root,
+            NodeIndex::Branch(idx) => &self.sg.arena[idx].value,
+        };
+
+        Some((parent_value, &current_child.value))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct StackState {
+    parent: NodeIndex,
+    current_child: Index,
+}
+
+impl StackState {
+    fn new(parent: NodeIndex, first_child: Index) -> Self {
+        Self {
+            parent,
+            current_child: first_child,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scene_graph_returns_nothing_on_empty_iteration() {
+        let scene_graph = SceneGraph::new("Root");
+        let mut scratch = TraversalScratch::new();
+
+        assert!(scene_graph.iter_with_buffer(&mut scratch).next().is_none());
+    }
+
+    #[test]
+    fn buffered_iteration_matches_iter() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach(grandchild, "First Great-Grandchild").unwrap();
+        sg.attach(first_child, "Second Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+        sg.attach(second_child, "Third Grandchild").unwrap();
+
+        let mut scratch = TraversalScratch::new();
+        assert_eq!(
+            Vec::from_iter(sg.iter_with_buffer(&mut scratch)),
+            Vec::from_iter(sg.iter())
+        );
+    }
+
+    #[test]
+    fn buffer_is_reused() {
+        let mut sg = SceneGraph::new(0);
+        let mut parent = NodeIndex::Root;
+        for i in 1..10 {
+            parent = sg.attach(parent, i).unwrap();
+        }
+
+        let mut scratch = TraversalScratch::with_capacity(16);
+        let capacity = scratch.capacity();
+
+        // stopping early leaves junk in the buffer, which gets cleared out next time
+        assert_eq!(sg.iter_with_buffer(&mut scratch).nth(3), Some((&3, &4)));
+
+        // the graph can change in between uses
+        sg.attach_at_root(100);
+        assert_eq!(
+            Vec::from_iter(sg.iter_with_buffer(&mut scratch).map(|(_, v)| *v)),
+            vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 100]
+        );
+        assert_eq!(scratch.capacity(), capacity);
+    }
+}
@@ -7,6 +7,8 @@ use crate::{Children, NodeIndex, SceneGraph};
 pub struct SceneGraphChildIter<'a, T> {
     sg: &'a SceneGraph<T>,
     current_node: Option<thunderdome::Index>,
+    current_back_node: Option<thunderdome::Index>,
+    remaining: usize,
 }
 
 impl<'a, T> SceneGraphChildIter<'a, T> {
@@ -23,6 +25,8 @@ impl<'a, T> SceneGraphChildIter<'a, T> {
         SceneGraphChildIter {
             sg,
             current_node: children.map(|v| v.first),
+            current_back_node: children.map(|v| v.last),
+            remaining: children.map(|v| v.count).unwrap_or_default(),
         }
     }
 }
@@ -31,13 +35,39 @@ impl<'a, T> Iterator for SceneGraphChildIter<'a, T> {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
+        // once the front and back cursors meet, we're done on both ends
+        if self.remaining == 0 {
+            return None;
+        }
+
         let yield_me = self.sg.arena.get(self.current_node?).unwrap();
         self.current_node = yield_me.next_sibling;
+        self.remaining -= 1;
 
         Some(&yield_me.value)
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
 }
 
+impl<'a, T> DoubleEndedIterator for SceneGraphChildIter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let yield_me = self.sg.arena.get(self.current_back_node?).unwrap();
+        self.current_back_node = yield_me.last_sibling;
+        self.remaining -= 1;
+
+        Some(&yield_me.value)
+    }
+}
+
+impl<'a, T> ExactSizeIterator for SceneGraphChildIter<'a, T> {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -69,4 +99,65 @@ mod tests {
             vec!["First Grandchild", "Second Grandchild", "Third Grandchild"]
         );
     }
+
+    #[test]
+    fn reverse_iteration() {
+        let mut sg = SceneGraph::new("Root");
+        assert!(sg.iter_direct_children(NodeIndex::Root).unwrap().next_back().is_none());
+
+        let first_child = sg.attach_at_root("First Child");
+        sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach_at_root("Second Child");
+        sg.attach_at_root("Third Child");
+
+        assert_eq!(
+            Vec::from_iter(sg.iter_direct_children(NodeIndex::Root).unwrap().rev().cloned()),
+            vec!["Third Child", "Second Child", "First Child"]
+        );
+    }
+
+    #[test]
+    fn iteration_from_both_ends() {
+        let mut sg = SceneGraph::new("Root");
+        sg.attach_at_root("First Child");
+        sg.attach_at_root("Second Child");
+        sg.attach_at_root("Third Child");
+
+        let mut iter = sg.iter_direct_children(NodeIndex::Root).unwrap();
+        assert_eq!(iter.next(), Some(&"First Child"));
+        assert_eq!(iter.next_back(), Some(&"Third Child"));
+        assert_eq!(iter.next_back(), Some(&"Second Child"));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        let mut iter = sg.iter_direct_children(NodeIndex::Root).unwrap();
+        assert_eq!(iter.next_back(), Some(&"Third Child"));
+        assert_eq!(iter.next(), Some(&"First Child"));
+        assert_eq!(iter.next(), Some(&"Second Child"));
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn exact_size() {
+        let mut sg = SceneGraph::new("Root");
+        assert_eq!(sg.iter_direct_children(NodeIndex::Root).unwrap().len(), 0);
+
+        let first_child = sg.attach_at_root("First Child");
+        sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach_at_root("Second Child");
+        sg.attach_at_root("Third Child");
+
+        let mut iter = sg.iter_direct_children(NodeIndex::Root).unwrap();
+        assert_eq!(iter.len(), 3);
+        iter.next();
+        assert_eq!(iter.len(), 2);
+        iter.next_back();
+        assert_eq!(iter.size_hint(), (1, Some(1)));
+        assert_eq!(iter.count(), 1);
+
+        let iter = sg.iter_direct_children(NodeIndex::Root).unwrap();
+        let len = iter.len();
+        assert_eq!(Vec::from_iter(iter).len(), len);
+    }
 }
@@ -0,0 +1,97 @@
+use crate::{NodeIndex, SceneGraph};
+
+/// A mutable iterator over only the immediate children of a node in a [SceneGraph].
+/// See [iter_direct_children_mut] for more information.
+///
+/// [iter_direct_children_mut]: SceneGraph::iter_direct_children_mut
+pub struct SceneGraphChildIterMut<'a, T> {
+    sg: &'a mut SceneGraph<T>,
+    current_node: Option<thunderdome::Index>,
+}
+
+impl<'a, T> SceneGraphChildIterMut<'a, T> {
+    pub(crate) fn new(sg: &'a mut SceneGraph<T>, root_index: NodeIndex) -> Self {
+        let children = match root_index {
+            NodeIndex::Root => sg.root_children,
+            NodeIndex::Branch(idx) => sg.arena[idx].children,
+        };
+
+        SceneGraphChildIterMut {
+            sg,
+            current_node: children.map(|v| v.first),
+        }
+    }
+}
+
+impl<'a, T> Iterator for SceneGraphChildIterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let yield_me = self.sg.arena.get_mut(self.current_node?).unwrap();
+        self.current_node = yield_me.next_sibling;
+
+        // safety: this is a lifetime extension, which i know is valid because every sibling is a
+        // distinct slot in the arena, and we only ever walk forward, so no value is yielded twice.
+        // like `SceneGraphIterMut`, this only holds up under miri's Tree Borrows.
+        let value: &mut T = unsafe { &mut *(&mut yield_me.value as *mut _) };
+
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scene_graph_returns_nothing_on_empty_iteration() {
+        let mut scene_graph = SceneGraph::new("Root");
+
+        assert!(
+            scene_graph
+                .iter_direct_children_mut(NodeIndex::Root)
+                .unwrap()
+                .next()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn mutation_is_observed() {
+        let mut sg = SceneGraph::new(0);
+        let first_child = sg.attach_at_root(1);
+        sg.attach(first_child, 10).unwrap();
+        sg.attach(first_child, 20).unwrap();
+        sg.attach(first_child, 30).unwrap();
+        sg.attach_at_root(2);
+
+        for value in sg.iter_direct_children_mut(first_child).unwrap() {
+            *value += 1;
+        }
+
+        assert_eq!(
+            Vec::from_iter(sg.iter().map(|(_parent, value)| *value)),
+            vec![1, 11, 21, 31, 2]
+        );
+    }
+
+    #[test]
+    fn values_never_alias() {
+        let mut sg = SceneGraph::new(0);
+        sg.attach_at_root(1);
+        let second_child = sg.attach_at_root(2);
+        sg.attach(second_child, 3).unwrap();
+        sg.attach_at_root(4);
+
+        let values = Vec::from_iter(sg.iter_direct_children_mut(NodeIndex::Root).unwrap());
+        for value in values {
+            *value *= 10;
+        }
+
+        assert_eq!(
+            Vec::from_iter(sg.iter_direct_children(NodeIndex::Root).unwrap().copied()),
+            vec![10, 20, 40]
+        );
+        assert_eq!(sg[sg.first_child(second_child).unwrap()], 3);
+    }
+}
@@ -0,0 +1,253 @@
+use crate::{NodeIndex, ParentNodeNotFound, RemoveError, SceneGraph};
+
+/// A read-only navigator over a [SceneGraph], which walks its links one step at a time. See
+/// [cursor] for more information.
+///
+/// [cursor]: SceneGraph::cursor
+pub struct Cursor<'a, T> {
+    sg: &'a SceneGraph<T>,
+    node_index: NodeIndex,
+}
+
+impl<'a, T> Cursor<'a, T> {
+    pub(crate) fn new(sg: &'a SceneGraph<T>, node_index: NodeIndex) -> Self {
+        Self { sg, node_index }
+    }
+
+    /// Returns the index of the node the cursor is on.
+    pub fn index(&self) -> NodeIndex {
+        self.node_index
+    }
+
+    /// Returns the value of the node the cursor is on, which is the root value for the Root. This
+    /// only returns `None` if the cursor was started on a node which doesn't exist.
+    pub fn value(&self) -> Option<&'a T> {
+        self.sg.get_value(self.node_index)
+    }
+
+    /// Moves to the parent of the current node. Returns `None` on the Root.
+    pub fn parent(&self) -> Option<Self> {
+        self.sg.parent(self.node_index).map(|idx| Self::new(self.sg, idx))
+    }
+
+    /// Moves to the first child of the current node. Returns `None` if it has no children.
+    pub fn first_child(&self) -> Option<Self> {
+        self.sg.first_child(self.node_index).map(|idx| Self::new(self.sg, idx))
+    }
+
+    /// Moves to the next sibling of the current node. Returns `None` if it's the last child of its
+    /// parent, or the Root.
+    pub fn next_sibling(&self) -> Option<Self> {
+        let next_sibling = self.sg.get(self.node_index)?.next_sibling()?;
+
+        Some(Self::new(self.sg, next_sibling))
+    }
+
+    /// Moves to the previous sibling of the current node. Returns `None` if it's the first child of
+    /// its parent, or the Root.
+    pub fn prev_sibling(&self) -> Option<Self> {
+        let previous_sibling = self.sg.get(self.node_index)?.previous_sibling()?;
+
+        Some(Self::new(self.sg, previous_sibling))
+    }
+}
+
+impl<'a, T> Clone for Cursor<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for Cursor<'a, T> {}
+
+/// A navigator over a [SceneGraph] which can also edit the graph where it stands. See
+/// [cursor_mut] for more information.
+///
+/// Since it holds the graph mutably, it moves in place rather than handing out new cursors like
+/// [Cursor] does. Each `move_to_` method returns `false`, and leaves the cursor where it was, if
+/// there's nowhere to go.
+///
+/// [cursor_mut]: SceneGraph::cursor_mut
+pub struct CursorMut<'a, T> {
+    sg: &'a mut SceneGraph<T>,
+    node_index: NodeIndex,
+}
+
+impl<'a, T> CursorMut<'a, T> {
+    pub(crate) fn new(sg: &'a mut SceneGraph<T>, node_index: NodeIndex) -> Self {
+        Self { sg, node_index }
+    }
+
+    /// Returns the index of the node the cursor is on.
+    pub fn index(&self) -> NodeIndex {
+        self.node_index
+    }
+
+    /// Returns a read-only [Cursor] on the current node, for looking around without moving.
+    pub fn as_cursor(&self) -> Cursor<'_, T> {
+        Cursor::new(self.sg, self.node_index)
+    }
+
+    /// Returns the value of the node the cursor is on. See [Cursor::value].
+    pub fn value(&self) -> Option<&T> {
+        self.as_cursor().value()
+    }
+
+    /// Returns the value of the node the cursor is on mutably, which is the root value for the Root.
+    pub fn value_mut(&mut self) -> Option<&mut T> {
+        self.sg.get_value_mut(self.node_index)
+    }
+
+    /// Moves to the parent of the current node.
+    pub fn move_to_parent(&mut self) -> bool {
+        self.move_to(self.sg.parent(self.node_index))
+    }
+
+    /// Moves to the first child of the current node.
+    pub fn move_to_first_child(&mut self) -> bool {
+        self.move_to(self.sg.first_child(self.node_index))
+    }
+
+    /// Moves to the next sibling of the current node.
+    pub fn move_to_next_sibling(&mut self) -> bool {
+        self.move_to(self.as_cursor().next_sibling().map(|cursor| cursor.index()))
+    }
+
+    /// Moves to the previous sibling of the current node.
+    pub fn move_to_prev_sibling(&mut self) -> bool {
+        self.move_to(self.as_cursor().prev_sibling().map(|cursor| cursor.index()))
+    }
+
+    /// Attaches a new last child to the current node, returning a handle to it. The cursor stays
+    /// where it is.
+    pub fn attach_child(&mut self, value: T) -> Result<NodeIndex, ParentNodeNotFound> {
+        self.sg.attach(self.node_index, value)
+    }
+
+    /// Removes the current node and all of its descendants, and moves the cursor to its parent.
+    ///
+    /// Like [SceneGraph::remove], trying to remove the Root returns `Err`. Unlike it, so does trying
+    /// to remove a node which doesn't exist, and the cursor stays where it is.
+    pub fn remove_current(&mut self) -> Result<(), RemoveError> {
+        if self.node_index.is_root() {
+            return Err(RemoveError::CannotRemoveRoot);
+        }
+        let parent = self.sg.parent(self.node_index).ok_or(RemoveError::NodeDoesNotExist)?;
+
+        self.sg.remove(self.node_index).unwrap();
+        self.node_index = parent;
+
+        Ok(())
+    }
+
+    fn move_to(&mut self, node_index: Option<NodeIndex>) -> bool {
+        match node_index {
+            Some(node_index) => {
+                self.node_index = node_index;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn walk_around() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        sg.attach(first_child, "First Grandchild").unwrap();
+        let second_grandchild = sg.attach(first_child, "Second Grandchild").unwrap();
+        sg.attach_at_root("Second Child");
+
+        let root = sg.cursor(NodeIndex::Root);
+        assert_eq!(root.value(), Some(&"Root"));
+        assert!(root.parent().is_none());
+        assert!(root.next_sibling().is_none());
+
+        let first = root.first_child().unwrap();
+        assert_eq!(first.index(), first_child);
+        assert!(first.prev_sibling().is_none());
+
+        let second = first.next_sibling().unwrap();
+        assert_eq!(second.value(), Some(&"Second Child"));
+        assert!(second.first_child().is_none());
+        assert!(second.next_sibling().is_none());
+        assert_eq!(second.prev_sibling().unwrap().index(), first_child);
+
+        let grandchild = first.first_child().unwrap().next_sibling().unwrap();
+        assert_eq!(grandchild.index(), second_grandchild);
+        assert_eq!(grandchild.value(), Some(&"Second Grandchild"));
+        assert_eq!(grandchild.parent().unwrap().parent().unwrap().index(), NodeIndex::Root);
+    }
+
+    #[test]
+    fn edit_with_cursor_mut() {
+        let mut sg = SceneGraph::new(0);
+        let first_child = sg.attach_at_root(1);
+        sg.attach(first_child, 10).unwrap();
+        let second_child = sg.attach_at_root(2);
+
+        let mut cursor = sg.cursor_mut(NodeIndex::Root);
+        assert!(!cursor.move_to_parent());
+        assert!(cursor.move_to_first_child());
+        assert!(cursor.move_to_next_sibling());
+        assert!(!cursor.move_to_next_sibling());
+        assert_eq!(cursor.index(), second_child);
+
+        let new_child = cursor.attach_child(20).unwrap();
+        assert_eq!(cursor.index(), second_child);
+        assert!(cursor.move_to_first_child());
+        assert_eq!(cursor.index(), new_child);
+        *cursor.value_mut().unwrap() += 1;
+        assert_eq!(cursor.value(), Some(&21));
+
+        assert!(cursor.move_to_parent());
+        assert!(cursor.move_to_prev_sibling());
+        assert_eq!(cursor.as_cursor().first_child().unwrap().value(), Some(&10));
+        cursor.remove_current().unwrap();
+        assert_eq!(cursor.index(), NodeIndex::Root);
+        *cursor.value_mut().unwrap() = 100;
+        assert_eq!(cursor.remove_current(), Err(RemoveError::CannotRemoveRoot));
+
+        assert_eq!(*sg.root(), 100);
+        assert_eq!(Vec::from_iter(sg.iter().map(|(_, value)| *value)), vec![2, 21]);
+    }
+
+    #[test]
+    fn cursor_on_removed_node() {
+        let mut sg = SceneGraph::new("Root");
+        let child = sg.attach_at_root("First Child");
+        sg.remove(child).unwrap();
+
+        let cursor = sg.cursor(child);
+        assert!(cursor.value().is_none());
+        assert!(cursor.parent().is_none());
+        assert!(cursor.first_child().is_none());
+        assert!(cursor.next_sibling().is_none());
+    }
+
+    #[test]
+    fn remove_current_twice() {
+        let mut sg = SceneGraph::new("Root");
+        let child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(child, "First Grandchild").unwrap();
+        sg.attach_at_root("Second Child");
+
+        let mut cursor = sg.cursor_mut(grandchild);
+        cursor.remove_current().unwrap();
+        assert_eq!(cursor.index(), child);
+        cursor.remove_current().unwrap();
+        assert_eq!(cursor.index(), NodeIndex::Root);
+
+        // a cursor on a removed node can't remove it again, and doesn't move
+        let mut cursor = sg.cursor_mut(child);
+        assert_eq!(cursor.remove_current(), Err(RemoveError::NodeDoesNotExist));
+        assert_eq!(cursor.remove_current(), Err(RemoveError::NodeDoesNotExist));
+        assert_eq!(cursor.index(), child);
+        assert_eq!(sg.len(), 1);
+    }
+}
@@ -1,7 +1,7 @@
+use alloc::vec::Vec;
 use thunderdome::Arena;
 
 use crate::{Children, Node, NodeIndex};
-use std::collections::VecDeque;
 
 /// An iterator over the children of a node in a [SceneGraph].
 /// See [iter_detach] and [iter_detach_all] for more information.
@@ -13,7 +13,9 @@ use std::collections::VecDeque;
 /// [iter_detach_all]: crate::SceneGraph::iter_detach_from_root
 pub struct SceneGraphDetachIter<'a, T> {
     arena: &'a mut Arena<Node<T>>,
-    stacks: VecDeque<StackState<T>>,
+    // frames hold whole nodes, so these stay on the heap even with `smallvec`, or every iterator
+    // would carry a row of `Node<T>`s inline
+    stacks: Vec<StackState<T>>,
 }
 
 impl<'a, T> SceneGraphDetachIter<'a, T> {
@@ -22,10 +24,10 @@ impl<'a, T> SceneGraphDetachIter<'a, T> {
         head_index: NodeIndex,
         current_children: Option<Children>,
     ) -> Self {
-        let mut stacks = VecDeque::new();
+        let mut stacks = Vec::new();
 
         if let Some(children) = current_children {
-            stacks.push_front(StackState::new(
+            stacks.push(StackState::new(
                 head_index,
                 arena.remove(children.first).unwrap(),
                 NodeIndex::Branch(children.first),
@@ -40,11 +42,11 @@ impl<'a, T> Iterator for SceneGraphDetachIter<'a, T> {
 
     fn next(&mut self) -> Option<Self::Item> {
         // if we're out of stack frames, we die here
-        let stack_frame = self.stacks.pop_front()?;
+        let stack_frame = self.stacks.pop()?;
 
         // if there's a sibling, push it onto the to do list!
         if let Some(next_sibling) = stack_frame.current_child.next_sibling {
-            self.stacks.push_front(StackState::new(
+            self.stacks.push(StackState::new(
                 stack_frame.parent,
                 self.arena.remove(next_sibling).unwrap(),
                 NodeIndex::Branch(next_sibling),
@@ -58,7 +60,7 @@ impl<'a, T> Iterator for SceneGraphDetachIter<'a, T> {
                 self.arena.remove(children.first).unwrap(),
                 NodeIndex::Branch(children.first),
             );
-            self.stacks.push_front(new_stack);
+            self.stacks.push(new_stack);
         }
 
         Some(DetachedNode {
@@ -87,8 +89,8 @@ pub struct DetachedNode<T> {
     pub node_value: T,
 }
 
-impl<T> std::fmt::Debug for DetachedNode<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<T> core::fmt::Debug for DetachedNode<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("DetachedNode")
             .field("parent_idx", &self.parent_idx)
             .field("node_idx", &self.node_idx)
@@ -102,8 +104,8 @@ struct StackState<T> {
     current_child_idx: NodeIndex,
 }
 
-impl<T> std::fmt::Debug for StackState<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<T> core::fmt::Debug for StackState<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("StackState")
             .field("parent", &self.parent)
             .field("current_child", &self.current_child)
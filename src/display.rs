@@ -0,0 +1,277 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+use crate::{Children, Node, NodeIndex, SceneGraph};
+
+/// A pretty printer for a [SceneGraph], which renders it as an indented tree, like the output of the
+/// `tree` command. See [display_tree] for more information.
+///
+/// [display_tree]: SceneGraph::display_tree
+pub struct DisplayTree<'a, T> {
+    sg: &'a SceneGraph<T>,
+}
+
+impl<'a, T> DisplayTree<'a, T> {
+    pub(crate) fn new(sg: &'a SceneGraph<T>) -> Self {
+        Self { sg }
+    }
+}
+
+impl<'a, T: fmt::Display> fmt::Display for DisplayTree<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.sg.root)?;
+
+        // this is the node we're currently on at every depth, from the root's children down.
+        let mut levels = Vec::new();
+        if let Some(first_child) = self.sg.root_children.map(|v| v.first) {
+            levels.push(first_child);
+        }
+
+        while let Some((&current_idx, ancestors)) = levels.split_last() {
+            // ancestors with more siblings to come keep their line going
+            for ancestor in ancestors {
+                let connector = if self.sg.arena[*ancestor].next_sibling.is_some() {
+                    "│   "
+                } else {
+                    "    "
+                };
+                f.write_str(connector)?;
+            }
+
+            let node = &self.sg.arena[current_idx];
+            let connector = if node.next_sibling.is_some() { "├── " } else { "└── " };
+            writeln!(f, "{}{}", connector, node.value)?;
+
+            if let Some(first_child) = node.children.map(|v| v.first) {
+                levels.push(first_child);
+                continue;
+            }
+
+            // walk back up until we find someone with a sibling left to print
+            while let Some(idx) = levels.pop() {
+                if let Some(next_sibling) = self.sg.arena[idx].next_sibling {
+                    levels.push(next_sibling);
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Prints a `Node` like its normal `Debug` impl, but with its `value` too. See
+/// [debug_with_value] for more information.
+///
+/// [debug_with_value]: Node::debug_with_value
+pub(crate) struct DebugNodeWithValue<'a, T>(pub(crate) &'a Node<T>);
+
+impl<'a, T: fmt::Debug> fmt::Debug for DebugNodeWithValue<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Node")
+            .field("value", &self.0.value)
+            .field("parent", &self.0.parent)
+            .field("children", &self.0.children)
+            .field("next_sibling", &self.0.next_sibling)
+            .finish()
+    }
+}
+
+/// Prints a node and all of its descendants as a nested tree of values. See [debug_tree] for more
+/// information.
+///
+/// [debug_tree]: SceneGraph::debug_tree
+pub(crate) struct DebugTree<'a, T> {
+    pub(crate) sg: &'a SceneGraph<T>,
+    pub(crate) value: &'a T,
+    pub(crate) children: Option<&'a Children>,
+}
+
+impl<'a, T: fmt::Debug> fmt::Debug for DebugTree<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut node = f.debug_struct("Node");
+        node.field("value", self.value);
+        if self.children.is_some() {
+            node.field(
+                "children",
+                &DebugChildren {
+                    sg: self.sg,
+                    children: self.children,
+                },
+            );
+        }
+        node.finish()
+    }
+}
+
+struct DebugChildren<'a, T> {
+    sg: &'a SceneGraph<T>,
+    children: Option<&'a Children>,
+}
+
+impl<'a, T: fmt::Debug> fmt::Debug for DebugChildren<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_list();
+
+        let mut current_child = self.children.map(|v| v.first);
+        while let Some(idx) = current_child {
+            let node = &self.sg.arena[idx];
+            list.entry(&DebugTree {
+                sg: self.sg,
+                value: &node.value,
+                children: node.children.as_ref(),
+            });
+
+            current_child = node.next_sibling;
+        }
+
+        list.finish()
+    }
+}
+
+/// Renders the graph as a Graphviz `digraph`. See [to_dot] for more information.
+///
+/// [to_dot]: SceneGraph::to_dot
+pub(crate) fn to_dot<T: fmt::Display>(sg: &SceneGraph<T>) -> String {
+    use fmt::Write;
+
+    let mut output = String::from("digraph {\n");
+    // writing into a `String` can't fail, so we don't bother with the results here.
+    let _ = writeln!(
+        output,
+        "    {} [label=\"{}\", style=bold];",
+        dot_name(NodeIndex::Root),
+        escape_label(&sg.root)
+    );
+    for (_parent, idx, value) in sg.iter_with_indices() {
+        let _ = writeln!(output, "    {} [label=\"{}\"];", dot_name(idx), escape_label(value));
+    }
+
+    for (parent, idx) in sg.iter_edges() {
+        let _ = writeln!(output, "    {} -> {};", dot_name(parent), dot_name(idx));
+    }
+    output.push('}');
+
+    output
+}
+
+fn dot_name(node_index: NodeIndex) -> String {
+    match node_index {
+        NodeIndex::Root => "root".to_string(),
+        NodeIndex::Branch(idx) => format!("n{}", idx.to_bits()),
+    }
+}
+
+fn escape_label<T: fmt::Display>(value: &T) -> String {
+    let mut output = String::new();
+    for c in value.to_string().chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            c => output.push(c),
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_root_only() {
+        let sg = SceneGraph::new("Root");
+
+        assert_eq!(sg.display_tree().to_string(), "Root\n");
+    }
+
+    #[test]
+    fn display_tree() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let first_grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach(first_grandchild, "First Great-Grandchild").unwrap();
+        sg.attach(first_child, "Second Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+        sg.attach(second_child, "Third Grandchild").unwrap();
+
+        let expected = "\
+Root
+├── First Child
+│   ├── First Grandchild
+│   │   └── First Great-Grandchild
+│   └── Second Grandchild
+└── Second Child
+    └── Third Grandchild
+";
+
+        assert_eq!(sg.display_tree().to_string(), expected);
+    }
+
+    #[test]
+    fn debug_tree() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach_at_root("Second Child");
+
+        assert_eq!(
+            format!("{:?}", sg.debug_tree()),
+            r#"Node { value: "Root", children: [Node { value: "First Child", children: [Node { value: "First Grandchild" }] }, Node { value: "Second Child" }] }"#
+        );
+        assert_eq!(format!("{:?}", SceneGraph::new(0).debug_tree()), "Node { value: 0 }");
+    }
+
+    #[test]
+    fn debug_with_value() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let node = sg.get(first_child).unwrap();
+
+        assert!(!format!("{:?}", node).contains("First Child"));
+        assert_eq!(
+            format!("{:?}", node.debug_with_value()),
+            r#"Node { value: "First Child", parent: Root, children: None, next_sibling: None }"#
+        );
+    }
+
+    #[test]
+    fn dot_edges() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+
+        let dot = sg.to_dot();
+        assert!(dot.starts_with("digraph {\n"));
+        assert!(dot.ends_with('}'));
+        assert!(dot.contains(r#"root [label="Root", style=bold];"#));
+        assert!(dot.contains(&format!(r#"{} [label="First Grandchild"];"#, dot_name(grandchild))));
+
+        let edges = Vec::from_iter(dot.lines().filter(|line| line.contains("->")).map(|line| line.trim()));
+        assert_eq!(
+            edges,
+            vec![
+                format!("root -> {};", dot_name(first_child)),
+                format!("{} -> {};", dot_name(first_child), dot_name(grandchild)),
+                format!("root -> {};", dot_name(second_child)),
+            ]
+        );
+    }
+
+    #[test]
+    fn dot_escapes_labels() {
+        let mut sg = SceneGraph::new(r#"The "Root""#);
+        sg.attach_at_root("back\\slash");
+
+        let dot = sg.to_dot();
+        assert!(dot.contains(r#"[label="The \"Root\"", style=bold]"#));
+        assert!(dot.contains(r#"[label="back\\slash"]"#));
+    }
+}
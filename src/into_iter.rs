@@ -0,0 +1,83 @@
+use alloc::vec::Vec;
+use thunderdome::{Arena, Index};
+
+use crate::{Node, SceneGraph};
+
+/// An owning iterator over the SceneGraph, which consumes the graph and yields every value,
+/// starting with the root, in a depth first traversal. See [into_iter] for more information.
+///
+/// [into_iter]: SceneGraph::into_iter
+pub struct SceneGraphIntoIter<T> {
+    root: Option<T>,
+    arena: Arena<Node<T>>,
+    stacks: Vec<Index>,
+}
+
+impl<T> SceneGraphIntoIter<T> {
+    pub(crate) fn new(sg: SceneGraph<T>) -> Self {
+        let mut stacks = Vec::new();
+        if let Some(first_child) = sg.root_children.map(|v| v.first) {
+            stacks.push(first_child);
+        }
+
+        SceneGraphIntoIter {
+            root: Some(sg.root),
+            arena: sg.arena,
+            stacks,
+        }
+    }
+}
+
+impl<T> Iterator for SceneGraphIntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // the root always comes first
+        if let Some(root) = self.root.take() {
+            return Some(root);
+        }
+
+        // if we're out of stack frames, we die here
+        let current_child = self.arena.remove(self.stacks.pop()?).unwrap();
+
+        // if there's a sibling, push it onto the to do list!
+        if let Some(next_sibling) = current_child.next_sibling {
+            self.stacks.push(next_sibling);
+        }
+
+        if let Some(first_child) = current_child.children.map(|v| v.first) {
+            self.stacks.push(first_child);
+        }
+
+        Some(current_child.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::NodeIndex;
+
+    use super::*;
+
+    #[test]
+    fn into_iter_yields_root() {
+        let scene_graph = SceneGraph::new("Root");
+
+        assert_eq!(Vec::from_iter(scene_graph), vec!["Root"]);
+    }
+
+    #[test]
+    fn into_iter_matches_iter() {
+        let mut sg = SceneGraph::new("Root".to_string());
+        let root_idx = NodeIndex::Root;
+        let first_child = sg.attach(root_idx, "First Child".to_string()).unwrap();
+        sg.attach(first_child, "First Grandchild".to_string()).unwrap();
+        let second_child = sg.attach(root_idx, "Second Child".to_string()).unwrap();
+        sg.attach(second_child, "Second Grandchild".to_string()).unwrap();
+
+        let mut expected = vec![sg.root().clone()];
+        expected.extend(sg.iter().map(|(_parent, value)| value.clone()));
+
+        assert_eq!(Vec::from_iter(sg), expected);
+    }
+}
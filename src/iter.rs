@@ -1,58 +1,146 @@
-use crate::{Children, Node, SceneGraph};
+use thunderdome::Index;
+
+use crate::{Children, Node, NodeIndex, SceneGraph, Stack};
 
 /// An iterator over the SceneGraph. See [iter] for more information.
 /// 
 /// [iter]: SceneGraph::iter
 pub struct SceneGraphIter<'a, T> {
     sg: &'a SceneGraph<T>,
-    stacks: Vec<StackState<'a, T>>,
+    stacks: Stack<StackState<'a, T>>,
 }
 
 impl<'a, T> SceneGraphIter<'a, T> {
-    pub(crate) fn new(sg: &'a SceneGraph<T>, root_value: &'a T, root_children: Option<&'a Children>) -> Self {
-        let mut stacks = Vec::new();
+    pub(crate) fn new(
+        sg: &'a SceneGraph<T>,
+        root_idx: NodeIndex,
+        root_value: &'a T,
+        root_children: Option<&'a Children>,
+    ) -> Self {
+        let mut stacks = Stack::new();
         if let Some(first_child) = root_children.map(|v| v.first) {
-            stacks.push(StackState::new(root_value, &sg.arena[first_child]));
+            stacks.push(StackState::new(
+                root_idx,
+                root_value,
+                &sg.arena[first_child],
+                first_child,
+                1,
+            ));
         };
         SceneGraphIter { sg, stacks }
     }
-}
 
-impl<'a, T> Iterator for SceneGraphIter<'a, T> {
-    type Item = (&'a T, &'a T);
-
-    fn next(&mut self) -> Option<Self::Item> {
+    fn next_frame(&mut self) -> Option<StackState<'a, T>> {
         // if we're out of stack frames, we die here
         let stack_frame = self.stacks.pop()?;
 
         // if there's a sibling, push it onto the to do list!
         if let Some(next_sibling) = stack_frame.current_child.next_sibling {
-            self.stacks
-                .push(StackState::new(stack_frame.parent_value, &self.sg.arena[next_sibling]));
+            self.stacks.push(StackState::new(
+                stack_frame.parent_idx,
+                stack_frame.parent_value,
+                &self.sg.arena[next_sibling],
+                next_sibling,
+                stack_frame.depth,
+            ));
         }
 
         if let Some(first_child) = stack_frame.current_child.children.map(|v| v.first) {
             self.stacks.push(StackState::new(
+                NodeIndex::Branch(stack_frame.current_child_idx),
                 &stack_frame.current_child.value,
                 &self.sg.arena[first_child],
+                first_child,
+                stack_frame.depth + 1,
             ));
         }
 
+        Some(stack_frame)
+    }
+}
+
+impl<'a, T> Iterator for SceneGraphIter<'a, T> {
+    type Item = (&'a T, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let stack_frame = self.next_frame()?;
+
         Some((stack_frame.parent_value, &stack_frame.current_child.value))
     }
 }
 
+/// An iterator over the SceneGraph which yields the indices of each parent and child alongside the
+/// child's value. See [iter_with_indices] for more information.
+///
+/// [iter_with_indices]: SceneGraph::iter_with_indices
+pub struct SceneGraphIndexedIter<'a, T>(SceneGraphIter<'a, T>);
+
+impl<'a, T> SceneGraphIndexedIter<'a, T> {
+    pub(crate) fn new(iter: SceneGraphIter<'a, T>) -> Self {
+        Self(iter)
+    }
+}
+
+impl<'a, T> Iterator for SceneGraphIndexedIter<'a, T> {
+    type Item = (NodeIndex, NodeIndex, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let stack_frame = self.0.next_frame()?;
+
+        Some((
+            stack_frame.parent_idx,
+            NodeIndex::Branch(stack_frame.current_child_idx),
+            &stack_frame.current_child.value,
+        ))
+    }
+}
+
+/// An iterator over the SceneGraph which yields the depth of each node alongside its value. See
+/// [iter_with_depth] for more information.
+///
+/// [iter_with_depth]: SceneGraph::iter_with_depth
+pub struct SceneGraphDepthIter<'a, T>(SceneGraphIter<'a, T>);
+
+impl<'a, T> SceneGraphDepthIter<'a, T> {
+    pub(crate) fn new(iter: SceneGraphIter<'a, T>) -> Self {
+        Self(iter)
+    }
+}
+
+impl<'a, T> Iterator for SceneGraphDepthIter<'a, T> {
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let stack_frame = self.0.next_frame()?;
+
+        Some((stack_frame.depth, &stack_frame.current_child.value))
+    }
+}
+
 #[derive(Debug)]
 struct StackState<'a, T> {
+    parent_idx: NodeIndex,
     parent_value: &'a T,
     current_child: &'a Node<T>,
+    current_child_idx: Index,
+    /// depth of `current_child`, relative to the node the iteration started at.
+    depth: usize,
 }
 
 impl<'a, T> StackState<'a, T> {
-    fn new(parent: &'a T, first_child: &'a Node<T>) -> Self {
+    fn new(
+        parent_idx: NodeIndex,
+        parent: &'a T,
+        first_child: &'a Node<T>,
+        first_child_idx: Index,
+        depth: usize,
+    ) -> Self {
         Self {
+            parent_idx,
             parent_value: parent,
             current_child: first_child,
+            current_child_idx: first_child_idx,
+            depth,
         }
     }
 }
@@ -98,6 +186,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn indexed_iteration() {
+        let mut sg = SceneGraph::new("Root");
+        let root_idx = NodeIndex::Root;
+        let first_child = sg.attach(root_idx, "First Child").unwrap();
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        let second_child = sg.attach(root_idx, "Second Child").unwrap();
+
+        let indexed = Vec::from_iter(sg.iter_with_indices());
+        assert_eq!(
+            indexed,
+            vec![
+                (root_idx, first_child, &"First Child"),
+                (first_child, grandchild, &"First Grandchild"),
+                (root_idx, second_child, &"Second Child"),
+            ]
+        );
+
+        for (parent_idx, child_idx, value) in indexed {
+            let node = sg.get(child_idx).unwrap();
+            assert_eq!(&node.value, value);
+            assert_eq!(node.parent(), parent_idx);
+        }
+    }
+
+    #[test]
+    fn depth_iteration() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach(grandchild, "First Great-Grandchild").unwrap();
+        sg.attach(first_child, "Second Grandchild").unwrap();
+        sg.attach_at_root("Second Child");
+
+        assert_eq!(
+            Vec::from_iter(sg.iter_with_depth()),
+            vec![
+                (1, &"First Child"),
+                (2, &"First Grandchild"),
+                (3, &"First Great-Grandchild"),
+                (2, &"Second Grandchild"),
+                (1, &"Second Child"),
+            ]
+        );
+    }
+
     #[test]
     fn single_iteration() {
         let mut sg = SceneGraph::new("Root");
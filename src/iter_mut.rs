@@ -1,17 +1,19 @@
 use thunderdome::Index;
 
-use crate::{Node, NodeIndex, SceneGraph};
+use crate::{NodeIndex, SceneGraph, Stack};
 
 /// A mutable iterator over the children of a node in a [SceneGraph].
 /// See [SceneGraph::iter_mut] for more information.
+///
+/// Every node is yielded exactly once, so the references can all be held at the same time.
 pub struct SceneGraphIterMut<'a, T> {
     sg: &'a mut SceneGraph<T>,
-    stacks: Vec<StackState>,
+    stacks: Stack<StackState>,
 }
 
 impl<'a, T> SceneGraphIterMut<'a, T> {
     pub(crate) fn new(sg: &'a mut SceneGraph<T>, root_node_idx: NodeIndex) -> Self {
-        let mut stacks = Vec::new();
+        let mut stacks = Stack::new();
 
         let first_child = match root_node_idx {
             NodeIndex::Root => sg.root_children.map(|v| v.first),
@@ -25,33 +27,11 @@ impl<'a, T> SceneGraphIterMut<'a, T> {
     }
 }
 
-impl<'a, T> Iterator for SceneGraphIterMut<'a, T> {
-    type Item = (&'a mut T, &'a mut T);
-
-    fn next(&mut self) -> Option<Self::Item> {
+impl<'a, T> SceneGraphIterMut<'a, T> {
+    fn next_frame(&mut self) -> Option<(StackState, &'a mut T)> {
         // if we're out of stack frames, we die here
         let stack_frame = self.stacks.pop()?;
-
-        let (parent, current_child) = match stack_frame.parent {
-            NodeIndex::Root => {
-                let parent = &mut self.sg.root;
-
-                let child = self.sg.arena.get_mut(stack_frame.current_child).unwrap();
-
-                (parent, child)
-            }
-            NodeIndex::Branch(idx) => {
-                let (parent, current_child) = self.sg.arena.get2_mut(idx, stack_frame.current_child);
-
-                (&mut parent.unwrap().value, current_child.unwrap())
-            }
-        };
-
-        // safety:  this is a lifetime extension, which i know is valid because get2_mut
-        // panics when we pass in two of the same things, and this iterator requires `&mut SG`
-        // to call `next`.
-        let (parent, current_child): (&mut T, &mut Node<T>) =
-            unsafe { (&mut *(parent as *mut _), &mut *(current_child as *mut _)) };
+        let current_child = self.sg.arena.get_mut(stack_frame.current_child).unwrap();
 
         // if there's a sibling, push it onto the to do list!
         if let Some(next_sibling) = current_child.next_sibling {
@@ -65,11 +45,56 @@ impl<'a, T> Iterator for SceneGraphIterMut<'a, T> {
             ));
         }
 
-        Some((parent, &mut current_child.value))
+        // safety: this is a lifetime extension. every node is pushed onto the stack exactly once, and
+        // we're done reading its links above, so this value is never touched again by the iterator.
+        // we hold `&mut SG` for `'a`, so nothing else can touch the arena meanwhile.
+        //
+        // note: miri's default Stacked Borrows rejects this, since `Arena::get_mut` reborrows the
+        // whole backing storage on each call. Tree Borrows accepts it, and `children_never_alias`
+        // is run under it in CI.
+        let value: &mut T = unsafe { &mut *(&mut current_child.value as *mut _) };
+
+        Some((stack_frame, value))
+    }
+}
+
+impl<'a, T> Iterator for SceneGraphIterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, current_child) = self.next_frame()?;
+
+        Some(current_child)
+    }
+}
+
+/// A mutable iterator over the SceneGraph which yields the indices of each parent and child
+/// alongside the child's value. See [iter_mut_with_indices] for more information.
+///
+/// [iter_mut_with_indices]: SceneGraph::iter_mut_with_indices
+pub struct SceneGraphIndexedIterMut<'a, T>(SceneGraphIterMut<'a, T>);
+
+impl<'a, T> SceneGraphIndexedIterMut<'a, T> {
+    pub(crate) fn new(iter: SceneGraphIterMut<'a, T>) -> Self {
+        Self(iter)
+    }
+}
+
+impl<'a, T> Iterator for SceneGraphIndexedIterMut<'a, T> {
+    type Item = (NodeIndex, NodeIndex, &'a mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (stack_frame, current_child) = self.0.next_frame()?;
+
+        Some((
+            stack_frame.parent,
+            NodeIndex::Branch(stack_frame.current_child),
+            current_child,
+        ))
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 struct StackState {
     parent: NodeIndex,
     current_child: Index,
@@ -105,7 +130,7 @@ mod tests {
         sg.attach(second_child, "First Grandchild").unwrap();
 
         assert_eq!(
-            Vec::from_iter(sg.iter_mut().map(|(_parent, value)| &*value).copied()),
+            Vec::from_iter(sg.iter_mut().map(|value| &*value).copied()),
             vec!["First Child", "Second Child", "First Grandchild"]
         );
     }
@@ -118,11 +143,143 @@ mod tests {
         sg.attach(child, "Second Child").unwrap();
 
         assert_eq!(
-            Vec::from_iter(sg.iter_mut().map(|(_parent, value)| &*value).copied()),
+            Vec::from_iter(sg.iter_mut().map(|value| &*value).copied()),
             vec!["First Child", "Second Child"]
         );
     }
 
+    #[test]
+    fn children_never_alias() {
+        use core::cell::RefCell;
+
+        let mut sg = SceneGraph::new(RefCell::new(0));
+        let first_child = sg.attach_at_root(RefCell::new(1));
+        let grandchild = sg.attach(first_child, RefCell::new(2)).unwrap();
+        sg.attach(grandchild, RefCell::new(3)).unwrap();
+        sg.attach(first_child, RefCell::new(4)).unwrap();
+        sg.attach_at_root(RefCell::new(5));
+
+        sg.for_each_mut(|parent, child| {
+            if let Some(parent) = parent {
+                *child.borrow_mut() += *parent.borrow() * 10;
+            }
+        });
+
+        // every node is yielded only once, so they can all be held together. `borrow_mut` panics if
+        // the same cell is already borrowed, which catches any aliasing, and writing through the
+        // first value after the last was yielded is what miri checks.
+        let children = Vec::from_iter(sg.iter_mut());
+        let borrowed = Vec::from_iter(children.iter().map(|child| child.borrow_mut()));
+        assert_eq!(Vec::from_iter(borrowed.iter().map(|v| **v)), vec![1, 12, 123, 14, 5]);
+        drop(borrowed);
+        for child in children {
+            *child.get_mut() += 1;
+        }
+        assert_eq!(
+            Vec::from_iter(sg.iter().map(|(_parent, child)| *child.borrow())),
+            vec![2, 13, 124, 15, 6]
+        );
+
+        let values = Vec::from_iter(sg.iter_direct_children_mut(first_child).unwrap());
+        let borrowed = Vec::from_iter(values.iter().map(|child| child.borrow_mut()));
+        assert_eq!(borrowed.len(), 2);
+        drop(borrowed);
+
+        let [a, b] = sg.get_many_mut([first_child, grandchild]).unwrap();
+        let (a, b) = (a.borrow_mut(), b.borrow_mut());
+        assert_eq!((*a, *b), (2, 13));
+    }
+
+    #[test]
+    fn indexed_iteration() {
+        let mut sg = SceneGraph::new(0);
+        let root_idx = NodeIndex::Root;
+        let first_child = sg.attach(root_idx, 1).unwrap();
+        let grandchild = sg.attach(first_child, 2).unwrap();
+        let second_child = sg.attach(root_idx, 3).unwrap();
+
+        let mut seen = vec![];
+        for (parent_idx, child_idx, value) in sg.iter_mut_with_indices() {
+            *value *= 10;
+            seen.push((parent_idx, child_idx));
+        }
+
+        assert_eq!(
+            seen,
+            vec![
+                (root_idx, first_child),
+                (first_child, grandchild),
+                (root_idx, second_child)
+            ]
+        );
+        assert_eq!(sg.get(grandchild).unwrap().value, 20);
+        assert_eq!(sg.get(second_child).unwrap().value, 30);
+    }
+
+    #[test]
+    fn indexed_iteration_from_node() {
+        let mut sg = SceneGraph::new(0);
+        let first_child = sg.attach_at_root(1);
+        let grandchild = sg.attach(first_child, 2).unwrap();
+        let great_grandchild = sg.attach(grandchild, 3).unwrap();
+        let second_grandchild = sg.attach(first_child, 4).unwrap();
+        sg.attach_at_root(5);
+
+        let mut seen = vec![];
+        let mut to_remove = vec![];
+        for (parent_idx, child_idx, value) in sg.iter_mut_from_node_with_indices(first_child).unwrap() {
+            *value *= 10;
+            seen.push((parent_idx, child_idx));
+
+            // queue up a structural change for after we're done
+            if *value == 30 {
+                to_remove.push(child_idx);
+            }
+        }
+
+        assert_eq!(
+            seen,
+            vec![
+                (first_child, grandchild),
+                (grandchild, great_grandchild),
+                (first_child, second_grandchild)
+            ]
+        );
+        for (_, child_idx) in seen {
+            assert!(sg.get(child_idx).is_some());
+        }
+
+        assert_eq!(to_remove, vec![great_grandchild]);
+        for idx in to_remove {
+            sg.remove(idx).unwrap();
+        }
+
+        assert_eq!(sg.get(first_child).unwrap().value, 1);
+        assert_eq!(sg.get(grandchild).unwrap().value, 20);
+        assert_eq!(sg.get(second_grandchild).unwrap().value, 40);
+        assert!(sg.iter_mut_from_node_with_indices(great_grandchild).is_err());
+    }
+
+    #[test]
+    fn indexed_values_never_alias() {
+        let mut sg = SceneGraph::new(0);
+        let first_child = sg.attach_at_root(1);
+        sg.attach(first_child, 2).unwrap();
+        sg.attach(first_child, 3).unwrap();
+        sg.attach_at_root(4);
+
+        // hold every handle and value past the end of iteration, and only then mutate them
+        let held = Vec::from_iter(sg.iter_mut_from_node_with_indices(first_child).unwrap());
+        let mut handles = vec![];
+        for (_parent_idx, child_idx, value) in held {
+            *value *= 10;
+            handles.push(child_idx);
+        }
+
+        assert_eq!(Vec::from_iter(handles.iter().map(|idx| sg[*idx])), vec![20, 30]);
+        assert_eq!(Vec::from_iter(sg.iter().map(|(_parent, value)| *value)), vec![1, 20, 30, 4]);
+    }
+
     #[test]
     fn single_iteration() {
         let mut sg = SceneGraph::new("Root");
@@ -130,7 +287,7 @@ mod tests {
         sg.attach(root_idx, "First Child").unwrap();
 
         assert_eq!(
-            Vec::from_iter(sg.iter_mut().map(|(_parent, value)| &*value).copied()),
+            Vec::from_iter(sg.iter_mut().map(|value| &*value).copied()),
             vec!["First Child",]
         );
     }
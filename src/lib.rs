@@ -2,19 +2,161 @@
 #![deny(rust_2018_idioms)]
 #![deny(missing_docs)]
 #![deny(rustdoc::broken_intra_doc_links)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
-use std::{cmp::Eq, collections::HashMap};
+extern crate alloc;
+
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    string::String,
+    vec,
+    vec::Vec,
+};
+use core::{
+    cmp::{Eq, Ordering},
+    convert::Infallible,
+    hash::{Hash, Hasher},
+};
 use thunderdome::{Arena, Index};
 
+/// Emits a debug event through `log` when the `log` feature is enabled, and compiles to nothing
+/// otherwise.
+macro_rules! debug_event {
+    ($($arg:tt)+) => {
+        #[cfg(feature = "log")]
+        log::debug!($($arg)+);
+    };
+}
+
+/// The stack used by the depth first iterators. With the `smallvec` feature, shallow traversals
+/// never touch the heap.
+#[cfg(feature = "smallvec")]
+type Stack<T> = smallvec::SmallVec<[T; 16]>;
+#[cfg(not(feature = "smallvec"))]
+type Stack<T> = Vec<T>;
+
+// the graph and everything borrowing it should be exactly as thread safe as the values it holds.
+// these are never called, but they don't compile if an auto trait goes missing.
+const _: () = {
+    fn assert_send<S: Send>() {}
+    fn assert_sync<S: Sync>() {}
+
+    #[allow(dead_code)]
+    fn send_values<'a, T: Send + 'a>() {
+        assert_send::<SceneGraph<T>>();
+        assert_send::<Node<T>>();
+        assert_send::<SceneGraphIntoIter<T>>();
+        assert_send::<SceneGraphIterMut<'a, T>>();
+        assert_send::<SceneGraphIndexedIterMut<'a, T>>();
+        assert_send::<SceneGraphChildIterMut<'a, T>>();
+        assert_send::<SceneGraphDetachIter<'a, T>>();
+        assert_send::<DetachedNode<T>>();
+        assert_send::<CursorMut<'a, T>>();
+    }
+
+    #[allow(dead_code)]
+    fn sync_values<'a, T: Sync + 'a>() {
+        assert_sync::<SceneGraph<T>>();
+        assert_sync::<SceneGraphIterMut<'a, T>>();
+        assert_sync::<CursorMut<'a, T>>();
+        assert_send::<SceneGraphIter<'a, T>>();
+        assert_send::<SceneGraphIndexedIter<'a, T>>();
+        assert_send::<SceneGraphBfsIter<'a, T>>();
+        assert_send::<SceneGraphChildIter<'a, T>>();
+        assert_send::<SceneGraphAncestorIter<'a, T>>();
+        assert_send::<SceneGraphPostOrderIter<'a, T>>();
+        assert_send::<Cursor<'a, T>>();
+    }
+
+    #[allow(dead_code)]
+    fn always() {
+        assert_send::<NodeIndex>();
+        assert_sync::<NodeIndex>();
+        assert_send::<TraversalScratch>();
+        assert_sync::<TraversalScratch>();
+    }
+};
+
+/// Builds a [SceneGraph] from a nested tree of values, which is handy for test fixtures. Every
+/// value is followed by an optional `=> { .. }` with its children, separated by commas.
+///
+/// ```
+/// # use scene_graph::{scene_graph, NodeIndex, SceneGraph};
+/// let sg = scene_graph! {
+///     "Root" => {
+///         "A" => { "B", "C" },
+///         "D",
+///     }
+/// };
+///
+/// let mut manual = SceneGraph::new("Root");
+/// let a = manual.attach_at_root("A");
+/// manual.attach(a, "B").unwrap();
+/// manual.attach(a, "C").unwrap();
+/// manual.attach_at_root("D");
+///
+/// assert_eq!(sg, manual);
+/// ```
+#[macro_export]
+macro_rules! scene_graph {
+    ($root:expr $(,)?) => {
+        $crate::SceneGraph::new($root)
+    };
+    ($root:expr => { $($children:tt)* } $(,)?) => {{
+        // these are unused when there are no children
+        #[allow(unused_mut)]
+        let mut sg = $crate::SceneGraph::new($root);
+        $crate::__scene_graph_children!(sg, $crate::NodeIndex::Root, $($children)*);
+        sg
+    }};
+}
+
+/// Attaches each child for [scene_graph], one at a time.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __scene_graph_children {
+    ($sg:ident, $parent:expr $(,)?) => {};
+    ($sg:ident, $parent:expr, $value:expr => { $($children:tt)* } $(, $($rest:tt)*)?) => {
+        {
+            #[allow(unused_variables)]
+            let idx = $sg.attach($parent, $value).unwrap();
+            $crate::__scene_graph_children!($sg, idx, $($children)*);
+        }
+        $crate::__scene_graph_children!($sg, $parent, $($($rest)*)?);
+    };
+    ($sg:ident, $parent:expr, $value:expr $(, $($rest:tt)*)?) => {
+        $sg.attach($parent, $value).unwrap();
+        $crate::__scene_graph_children!($sg, $parent, $($($rest)*)?);
+    };
+}
+
+mod ancestor_iter;
+mod bfs_iter;
+mod buffered_iter;
 mod child_iter;
+mod child_iter_mut;
+mod cursor;
 mod detatch_iter;
+mod display;
+mod into_iter;
 mod iter;
 mod iter_mut;
+mod post_order_iter;
+#[cfg(feature = "serde")]
+mod serialize;
 
+pub use ancestor_iter::SceneGraphAncestorIter;
+pub use bfs_iter::SceneGraphBfsIter;
+pub use buffered_iter::{SceneGraphBufferedIter, TraversalScratch};
 pub use child_iter::SceneGraphChildIter;
+pub use child_iter_mut::SceneGraphChildIterMut;
+pub use cursor::{Cursor, CursorMut};
 pub use detatch_iter::{DetachedNode, SceneGraphDetachIter};
-pub use iter::SceneGraphIter;
-pub use iter_mut::SceneGraphIterMut;
+pub use display::DisplayTree;
+pub use into_iter::SceneGraphIntoIter;
+pub use iter::{SceneGraphDepthIter, SceneGraphIndexedIter, SceneGraphIter};
+pub use iter_mut::{SceneGraphIndexedIterMut, SceneGraphIterMut};
+pub use post_order_iter::SceneGraphPostOrderIter;
 
 /// The core structure of `scene-graph`. This forms a rose tree, similar to a geneological tree.
 /// In this crate, we use geneological terms like `parent`, `child`, and `sibling` to describe node
@@ -24,11 +166,18 @@ pub use iter_mut::SceneGraphIterMut;
 /// Nodes additionally have siblings, which is determined by order of insertion into the graph.
 ///
 /// You can traverse the SceneGraph by `iter`, to iterate downwards over the entire graph, or
-/// `iter_on_node`, to iterate downward from a particular node. This crate has no ability to iterate
-/// upwards, but you can use `get` to find a node's parent. If iterating upwards if useful to your
-/// usecase, please file an issue. Additionally, there are mutable variants of all of these
-/// iterators available.
-#[derive(Debug)]
+/// `iter_on_node`, to iterate downward from a particular node. To iterate upwards, use
+/// `iter_ancestors`, which walks from a node's parent up to the root. Additionally, there are
+/// mutable variants of the downward iterators available.
+///
+/// The root is special: it's always present, can't be removed, and isn't stored as a [Node], so it
+/// has no parent or siblings, and `get` returns `None` for it. Its value is available with `root`,
+/// and most methods which take a `NodeIndex` accept `NodeIndex::Root` for working with its
+/// children, such as `first_child` or `iter_direct_children`.
+///
+/// Cloning a SceneGraph deep copies every node, and any `NodeIndex` from the original graph
+/// refers to the same node in the clone.
+#[derive(Debug, Clone)]
 pub struct SceneGraph<T> {
     /// The root value of the scene graph.
     pub root: T,
@@ -46,16 +195,52 @@ impl<T> SceneGraph<T> {
         }
     }
 
+    /// Creates a new `SceneGraph` with space for `capacity` non-root nodes before reallocating.
+    pub fn with_capacity(root: T, capacity: usize) -> Self {
+        Self {
+            arena: Arena::with_capacity(capacity),
+            root,
+            root_children: None,
+        }
+    }
+
+    /// Creates a new `SceneGraph` with every item in `children` attached directly to the root, in
+    /// order.
+    pub fn from_root_and_children(root: T, children: impl IntoIterator<Item = T>) -> Self {
+        let children = children.into_iter();
+        let mut sg = Self::with_capacity(root, children.size_hint().0);
+        for child in children {
+            sg.attach_at_root(child);
+        }
+
+        sg
+    }
+
+    /// Returns the number of NON-ROOT nodes the graph can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.arena.capacity()
+    }
+
     /// Clears all nodes from `self`, leaving the `Root` in place. If you want to edit the root too,
     /// just make a new SceneGraph.
     ///
     /// Note: this method maintains the underlying container's size, so future attaches could have
-    /// some performance gains.
+    /// some performance gains. There's no way to give that memory back short of making a new
+    /// SceneGraph -- rebuilding the arena would reset its generations and let stale handles
+    /// point at new nodes.
     pub fn clear(&mut self) {
         self.arena.clear();
         self.root_children = None;
     }
 
+    /// Removes every non-root node, yielding their values in a depth first traversal. Like `clear`,
+    /// this maintains the underlying container's size.
+    ///
+    /// If the iterator is dropped early, the remaining nodes are still removed.
+    pub fn drain(&mut self) -> impl Iterator<Item = T> + '_ {
+        self.iter_detach_from_root().map(|detached_node| detached_node.node_value)
+    }
+
     /// Returns the number of NON-ROOT nodes in the graph.
     pub fn len(&self) -> usize {
         self.arena.len()
@@ -78,21 +263,179 @@ impl<T> SceneGraph<T> {
         // push that node!
         let new_idx = self.arena.insert(Node::new(value, parent));
         self.place_node(parent, new_idx)?;
+        debug_event!("attached {:?} to {:?}", new_idx, parent);
+        debug_assert_eq!(self.check_links(NodeIndex::Branch(new_idx)), Ok(()), "broken links after attach");
+        #[cfg(scene_graph_validate)]
+        debug_assert_eq!(self.validate(), Ok(()), "broken graph after attach");
+
+        Ok(NodeIndex::Branch(new_idx))
+    }
+
+    /// Attaches every value in `values` to `parent`, after any children it already has, returning
+    /// their handles in order. The parent is only looked up and written back once, but that doesn't
+    /// make this measurably faster than calling `attach` in a loop.
+    ///
+    /// If `parent` doesn't exist, `values` is never iterated. If iterating `values` panics, the
+    /// values before the panic stay attached.
+    pub fn attach_many(
+        &mut self,
+        parent: NodeIndex,
+        values: impl IntoIterator<Item = T>,
+    ) -> Result<Vec<NodeIndex>, ParentNodeNotFound> {
+        let children = self.get_children(parent).map_err(|_| ParentNodeNotFound)?;
+        let mut guard = ChildrenGuard {
+            sg: self,
+            parent,
+            children,
+        };
+        let values = values.into_iter();
+        let mut new_indices = Vec::with_capacity(values.size_hint().0);
+
+        for value in values {
+            let mut node = Node::new(value, parent);
+            node.last_sibling = guard.children.map(|v| v.last);
+            let new_idx = guard.sg.arena.insert(node);
+
+            match guard.children.as_mut() {
+                Some(children) => {
+                    guard.sg.arena[children.last].next_sibling = Some(new_idx);
+                    children.last = new_idx;
+                    children.count += 1;
+                }
+                None => {
+                    guard.children = Some(Children {
+                        first: new_idx,
+                        last: new_idx,
+                        count: 1,
+                    });
+                }
+            }
+
+            new_indices.push(NodeIndex::Branch(new_idx));
+        }
+
+        drop(guard);
+        debug_event!("attached {} nodes to {:?}", new_indices.len(), parent);
+
+        Ok(new_indices)
+    }
+
+    /// Attaches a node to another node as its *first* child, returning a handle to it. Unlike
+    /// `attach`, the new node will come before all of its siblings in iteration order.
+    pub fn attach_front(&mut self, parent: NodeIndex, value: T) -> Result<NodeIndex, ParentNodeNotFound> {
+        if !self.contains(parent) {
+            return Err(ParentNodeNotFound);
+        }
+
+        let new_idx = self.arena.insert(Node::new(value, parent));
+        self.place_node_front(parent, new_idx)?;
+        debug_event!("attached {:?} to the front of {:?}", new_idx, parent);
+
+        Ok(NodeIndex::Branch(new_idx))
+    }
+
+    /// Attaches a node directly after `sibling`, as a child of the same parent, returning a handle
+    /// to it.
+    ///
+    /// The Root has no siblings, so passing it in returns `Err`.
+    pub fn attach_after(&mut self, sibling: NodeIndex, value: T) -> Result<NodeIndex, NodeDoesNotExist> {
+        let sibling = match sibling {
+            NodeIndex::Root => return Err(NodeDoesNotExist),
+            NodeIndex::Branch(idx) => idx,
+        };
+        let parent = self.arena.get(sibling).ok_or(NodeDoesNotExist)?.parent;
+
+        let new_idx = self.arena.insert(Node::new(value, parent));
+        self.place_node_after(sibling, new_idx);
+        debug_event!("attached {:?} after {:?}", new_idx, sibling);
+
+        Ok(NodeIndex::Branch(new_idx))
+    }
+
+    /// Attaches a node directly before `sibling`, as a child of the same parent, returning a handle
+    /// to it.
+    ///
+    /// The Root has no siblings, so passing it in returns `Err`.
+    pub fn attach_before(&mut self, sibling: NodeIndex, value: T) -> Result<NodeIndex, NodeDoesNotExist> {
+        let sibling = match sibling {
+            NodeIndex::Root => return Err(NodeDoesNotExist),
+            NodeIndex::Branch(idx) => idx,
+        };
+        let parent = self.arena.get(sibling).ok_or(NodeDoesNotExist)?.parent;
+
+        let new_idx = self.arena.insert(Node::new(value, parent));
+        self.place_node_before(sibling, new_idx);
+        debug_event!("attached {:?} before {:?}", new_idx, sibling);
+
+        Ok(NodeIndex::Branch(new_idx))
+    }
+
+    /// Attaches a new node in place of `child`, and moves `child` under it as its only child, like
+    /// wrapping `child` in a container. The new node takes `child`'s parent and position among its
+    /// siblings, and `child` keeps all of its own descendants. Returns a handle to the new node.
+    ///
+    /// The Root can't be wrapped, so passing it in returns `Err`.
+    pub fn insert_between(&mut self, child: NodeIndex, value: T) -> Result<NodeIndex, NodeDoesNotExist> {
+        let NodeIndex::Branch(child) = child else {
+            return Err(NodeDoesNotExist);
+        };
+        let parent = self.arena.get(child).ok_or(NodeDoesNotExist)?.parent;
+
+        let new_idx = self.arena.insert(Node::new(value, parent));
+        self.place_node_before(child, new_idx);
+
+        self.unlink_node(child);
+        self.arena[child].parent = NodeIndex::Branch(new_idx);
+        self.place_node(NodeIndex::Branch(new_idx), child).unwrap();
+        debug_event!("inserted {:?} between {:?} and {:?}", new_idx, parent, child);
 
         Ok(NodeIndex::Branch(new_idx))
     }
 
+    /// Attaches a node to `parent` in sorted position, directly before the first child which `cmp`
+    /// says is greater than it. If the children were already sorted with `cmp`, as by
+    /// [sort_children_by], they stay sorted, and a value which compares equal to some children goes
+    /// after them.
+    ///
+    /// This operation is O(n) over the number of siblings.
+    ///
+    /// [sort_children_by]: Self::sort_children_by
+    pub fn attach_sorted(
+        &mut self,
+        parent: NodeIndex,
+        value: T,
+        mut cmp: impl FnMut(&T, &T) -> Ordering,
+    ) -> Result<NodeIndex, ParentNodeNotFound> {
+        let children = self.get_children(parent).map_err(|_| ParentNodeNotFound)?;
+
+        let mut current_child = children.map(|v| v.first);
+        while let Some(idx) = current_child {
+            let sibling = &self.arena[idx];
+            if cmp(&value, &sibling.value) == Ordering::Less {
+                let new_idx = self.arena.insert(Node::new(value, parent));
+                self.place_node_before(idx, new_idx);
+                debug_event!("attached {:?} to {:?} before {:?}", new_idx, parent, idx);
+
+                return Ok(NodeIndex::Branch(new_idx));
+            }
+
+            current_child = sibling.next_sibling;
+        }
+
+        self.attach(parent, value)
+    }
+
     /// Attaches an entire scene graph to a place on this graph. The old root node will be at
     /// the returned NodeIndex.
     pub fn attach_graph(
         &mut self,
         parent: NodeIndex,
         mut other_graph: SceneGraph<T>,
-    ) -> Result<(NodeIndex, HashMap<NodeIndex, NodeIndex>), ParentNodeNotFound> {
+    ) -> Result<(NodeIndex, BTreeMap<NodeIndex, NodeIndex>), ParentNodeNotFound> {
         let other_root = other_graph.root;
         let new_root_idx = self.attach(parent, other_root)?;
 
-        let mut helper_map = HashMap::new();
+        let mut helper_map = BTreeMap::new();
         helper_map.insert(NodeIndex::Root, new_root_idx);
 
         let detach_iter = SceneGraphDetachIter::new(&mut other_graph.arena, NodeIndex::Root, other_graph.root_children);
@@ -107,6 +450,28 @@ impl<T> SceneGraph<T> {
         Ok((new_root_idx, helper_map))
     }
 
+    /// Attaches the children of another scene graph, and all of their descendants, as direct
+    /// children of this graph's root, after any children it already has. Returns a map from each
+    /// node's old `NodeIndex` in `other_graph` to its new `NodeIndex` in `self`.
+    ///
+    /// Note: the root value of `other_graph` is dropped. Use `attach_graph` to keep it.
+    pub fn merge_under_root(&mut self, mut other_graph: SceneGraph<T>) -> BTreeMap<NodeIndex, NodeIndex> {
+        let mut helper_map = BTreeMap::new();
+        helper_map.insert(NodeIndex::Root, NodeIndex::Root);
+
+        let detach_iter = SceneGraphDetachIter::new(&mut other_graph.arena, NodeIndex::Root, other_graph.root_children);
+
+        for detached_node in detach_iter {
+            let parent_place = helper_map[&detached_node.parent_idx];
+            let new_idx = self.attach(parent_place, detached_node.node_value).unwrap();
+
+            helper_map.insert(detached_node.node_idx, new_idx);
+        }
+        helper_map.remove(&NodeIndex::Root);
+
+        helper_map
+    }
+
     /// Removes a given node from the scene graph, returning a new SceneGraph where the given
     /// node is now the *root*.
     ///
@@ -119,13 +484,14 @@ impl<T> SceneGraph<T> {
         };
 
         let node = self.arena.remove(node_index)?;
+        debug_event!("detached {:?} from {:?}", node_index, node.parent);
         let mut new_sg = SceneGraph::new(node.value);
 
-        let mut helper_map = std::collections::HashMap::new();
+        let mut helper_map = BTreeMap::new();
         helper_map.insert(NodeIndex::Branch(node_index), NodeIndex::Root);
 
         for detached_node in SceneGraphDetachIter::new(&mut self.arena, NodeIndex::Branch(node_index), node.children) {
-            println!("detached_node = {:#?}", detached_node);
+            debug_event!("detached {:?} from {:?}", detached_node.node_idx, detached_node.parent_idx);
 
             let parent_place = match detached_node.parent_idx {
                 NodeIndex::Root => NodeIndex::Root,
@@ -137,527 +503,4892 @@ impl<T> SceneGraph<T> {
         }
 
         self.fix_parent(node.next_sibling, node.last_sibling, node.parent, node_index);
+        debug_assert_eq!(self.check_links(node.parent), Ok(()), "broken links after detach");
+        #[cfg(scene_graph_validate)]
+        debug_assert_eq!(self.validate(), Ok(()), "broken graph after detach");
 
         Some(new_sg)
     }
 
-    /// Moves a node from one parent to another parent. If this operation returns `Err`, then
-    /// nothing will have happened to the node.
-    pub fn move_node(&mut self, moving_node_idx: NodeIndex, new_parent: NodeIndex) -> Result<(), NodeDoesNotExist> {
-        let moving_node_idx = match moving_node_idx {
-            NodeIndex::Root => return Err(NodeDoesNotExist),
-            NodeIndex::Branch(idx) => {
-                if !self.arena.contains(idx) {
-                    return Err(NodeDoesNotExist);
-                }
-
-                idx
-            }
+    /// Removes a given node and all of its descendants from this graph, and attaches them to
+    /// `dest_parent` in `dest`, keeping their shape and sibling order. Returns the node's new
+    /// `NodeIndex` in `dest`.
+    ///
+    /// This is the same as a `detach` followed by an `attach_graph`, but skips building the
+    /// intermediate graph. Nothing is changed in either graph if this returns `Err`.
+    pub fn detach_into(
+        &mut self,
+        node_index: NodeIndex,
+        dest: &mut SceneGraph<T>,
+        dest_parent: NodeIndex,
+    ) -> Result<NodeIndex, TransferError> {
+        let NodeIndex::Branch(node_index) = node_index else {
+            return Err(TransferError::NodeDoesNotExist);
         };
-
-        if let NodeIndex::Branch(idx) = new_parent {
-            if !self.arena.contains(idx) {
-                return Err(NodeDoesNotExist);
-            }
+        if !self.arena.contains(node_index) {
+            return Err(TransferError::NodeDoesNotExist);
+        }
+        if !dest.contains(dest_parent) {
+            return Err(TransferError::ParentNodeNotFound);
         }
 
-        // okay, now we hot swap em
-        let moving_node = self.arena.get_mut(moving_node_idx).expect("we checked earlier");
-        let old_parent = moving_node.parent;
-        moving_node.parent = new_parent;
+        let node = self.arena.remove(node_index).unwrap();
+        self.fix_parent(node.next_sibling, node.last_sibling, node.parent, node_index);
+        let new_idx = dest.attach(dest_parent, node.value).unwrap();
+        debug_event!("transferred {:?} to {:?}", node_index, new_idx);
 
-        let next_sibling = moving_node.next_sibling;
-        moving_node.next_sibling = None;
-        let last_sibling = moving_node.last_sibling;
+        let mut helper_map = BTreeMap::new();
+        helper_map.insert(NodeIndex::Branch(node_index), new_idx);
 
-        // now let's fix our old dad
-        self.fix_parent(next_sibling, last_sibling, old_parent, moving_node_idx);
+        for detached_node in SceneGraphDetachIter::new(&mut self.arena, NodeIndex::Branch(node_index), node.children) {
+            let new_child_idx = dest.attach(helper_map[&detached_node.parent_idx], detached_node.node_value).unwrap();
 
-        // place it!
-        self.place_node(new_parent, moving_node_idx)
-            .expect("we checked earlier");
+            helper_map.insert(detached_node.node_idx, new_child_idx);
+        }
 
-        Ok(())
+        Ok(new_idx)
     }
 
-    /// Removes a node *without* returning anything. This can save a few allocations. This removes
-    /// all of its children as well.
-    pub fn remove(&mut self, node_index: NodeIndex) {
-        let index = match node_index {
-            NodeIndex::Root => panic!("you cannot remove the root"),
-            NodeIndex::Branch(index) => index,
-        };
+    /// Removes a given node from the scene graph, returning a new SceneGraph where the given node
+    /// is now the *root*. This gives the same graph as [detach], but is faster on big subtrees, since
+    /// each node is moved over in a single pass, with its new links carried along the traversal
+    /// instead of being looked up.
+    ///
+    /// Note: this always returns `None` when the node doesn't exist, or when the `node_index` is
+    /// the Root.
+    ///
+    /// [detach]: Self::detach
+    pub fn split_off(&mut self, node_index: NodeIndex) -> Option<SceneGraph<T>> {
+        let NodeIndex::Branch(node_index) = node_index else { return None };
 
-        let Some(node) = self.arena.remove(index) else { return };
+        let node = self.arena.remove(node_index)?;
+        self.fix_parent(node.next_sibling, node.last_sibling, node.parent, node_index);
+        debug_assert_eq!(self.check_links(node.parent), Ok(()), "broken links after split_off");
+        debug_event!("split off {:?} from {:?}", node_index, node.parent);
 
-        // detach em all!
-        for _v in SceneGraphDetachIter::new(&mut self.arena, node_index, node.children) {}
+        let mut new_sg = SceneGraph::new(node.value);
 
-        self.fix_parent(node.next_sibling, node.last_sibling, node.parent, index);
-    }
+        let mut stacks: Stack<SplitFrame> = Stack::new();
+        if let Some(children) = node.children {
+            stacks.push(SplitFrame::first_child(children, NodeIndex::Root));
+        }
 
-    /// Returns `true` is the given `node_index` is valid.
-    pub fn contains(&self, node_index: NodeIndex) -> bool {
-        match node_index {
-            NodeIndex::Root => true,
-            NodeIndex::Branch(idx) => self.arena.contains(idx),
+        while let Some(frame) = stacks.pop() {
+            let node = self.arena.remove(frame.idx).unwrap();
+            let mut new_node = Node::new(node.value, frame.new_parent);
+            new_node.last_sibling = frame.last_sibling;
+            let new_idx = new_sg.arena.insert(new_node);
+
+            if let Some(last_sibling) = frame.last_sibling {
+                new_sg.arena[last_sibling].next_sibling = Some(new_idx);
+            }
+            let first = frame.first.unwrap_or(new_idx);
+
+            // the parent is only written to once, by its last child, which knows the whole range
+            match node.next_sibling {
+                Some(next_sibling) => stacks.push(SplitFrame {
+                    idx: next_sibling,
+                    last_sibling: Some(new_idx),
+                    first: Some(first),
+                    ..frame
+                }),
+                None => {
+                    *new_sg.get_children_mut(frame.new_parent).unwrap() = Some(Children {
+                        first,
+                        last: new_idx,
+                        count: frame.count,
+                    });
+                }
+            }
+            if let Some(children) = node.children {
+                stacks.push(SplitFrame::first_child(children, NodeIndex::Branch(new_idx)));
+            }
         }
+
+        Some(new_sg)
     }
 
-    /// Gets a given node based on `NodeIndex`. Note that the `Root` always returns `None`.
-    /// Simply access `root_value` to get the root value.
-    pub fn get(&self, node_index: NodeIndex) -> Option<&Node<T>> {
-        match node_index {
-            NodeIndex::Root => None,
-            NodeIndex::Branch(idx) => self.arena.get(idx),
+    /// Replaces the given node and all of its descendants with the contents of `other`, which takes
+    /// the node's place among its siblings. The old subtree is dropped. The root of `other` reuses
+    /// the node's slot, so the returned `NodeIndex` is the same as `node_index`, but all of its
+    /// descendants get new ones.
+    ///
+    /// Like `detach`, the Root can't be replaced, so passing it in returns `Err`.
+    pub fn replace_subtree(
+        &mut self,
+        node_index: NodeIndex,
+        mut other: SceneGraph<T>,
+    ) -> Result<NodeIndex, NodeDoesNotExist> {
+        if node_index.is_root() || !self.contains(node_index) {
+            return Err(NodeDoesNotExist);
         }
-    }
 
-    /// Gets a given node based on `NodeIndex`. Note that the `Root` always returns `None`,
-    /// as it is not a true node. Use `get_children` to generically get children.
-    pub fn get_mut(&mut self, node_index: NodeIndex) -> Option<&mut Node<T>> {
-        match node_index {
-            NodeIndex::Root => None,
-            NodeIndex::Branch(idx) => self.arena.get_mut(idx),
+        for _v in self.iter_detach(node_index).unwrap() {}
+        self[node_index] = other.root;
+
+        let mut helper_map = BTreeMap::new();
+        helper_map.insert(NodeIndex::Root, node_index);
+
+        for detached_node in SceneGraphDetachIter::new(&mut other.arena, NodeIndex::Root, other.root_children) {
+            let new_idx = self.attach(helper_map[&detached_node.parent_idx], detached_node.node_value).unwrap();
+
+            helper_map.insert(detached_node.node_idx, new_idx);
         }
-    }
+        debug_event!("replaced the subtree at {:?}", node_index);
 
-    /// Gets the root node's value.
-    pub fn root(&self) -> &T {
-        &self.root
+        Ok(node_index)
     }
 
-    /// Gets the root node's value mutably.
-    pub fn root_mut(&mut self) -> &mut T {
-        &mut self.root
-    }
+    /// Copies the given node and all of its descendants into a new SceneGraph, with the node as the
+    /// new root, leaving `self` untouched. Like `detach`, this returns `None` for the root or a node
+    /// which doesn't exist. Just `clone` the whole graph to copy from the root.
+    pub fn clone_subtree(&self, node_index: NodeIndex) -> Option<SceneGraph<T>>
+    where
+        T: Clone,
+    {
+        if node_index.is_root() {
+            return None;
+        }
+        let node = self.get(node_index)?;
+        let mut new_sg = SceneGraph::new(node.value.clone());
 
-    /// Returns the parent NodeIndex of a given Node.
-    ///
-    /// This operation is O1 over the number of nodes in the SceneGraph.
-    /// Note: this returns `None` for the Root.
-    pub fn parent(&self, node_index: NodeIndex) -> Option<NodeIndex> {
-        self.get(node_index).map(|v| v.parent)
-    }
+        let mut helper_map = BTreeMap::new();
+        helper_map.insert(node_index, NodeIndex::Root);
 
-    /// Iterate mutably over the Scene Graph in a depth first traversal.
-    pub fn iter_mut(&mut self) -> SceneGraphIterMut<'_, T> {
-        SceneGraphIterMut::new(self, NodeIndex::Root)
-    }
+        let iter = SceneGraphIndexedIter::new(self.iter_from_node(node_index).unwrap());
+        for (parent_idx, child_idx, value) in iter {
+            let new_idx = new_sg.attach(helper_map[&parent_idx], value.clone()).unwrap();
+            helper_map.insert(child_idx, new_idx);
+        }
 
-    /// Iterate immutably over the Scene Graph in a depth first traversal.
-    pub fn iter(&self) -> SceneGraphIter<'_, T> {
-        self.iter_from_node(NodeIndex::Root).unwrap()
+        Some(new_sg)
     }
 
-    /// Iterate immutably over the Scene Graph out of order. This is useful for speed.
-    pub fn iter_out_of_order(&self) -> impl Iterator<Item = (NodeIndex, &T)> {
-        self.arena.iter().map(|(k, v)| (NodeIndex::Branch(k), &v.value))
+    /// Transforms every value in the graph, including the root, into a new SceneGraph with the
+    /// same shape and sibling order.
+    ///
+    /// Values are visited in a depth first traversal, starting with the root. The `NodeIndex`es in
+    /// the new graph will not match those of `self`.
+    pub fn map<U>(self, mut f: impl FnMut(T) -> U) -> SceneGraph<U> {
+        match self.try_map(|value| Ok::<U, Infallible>(f(value))) {
+            Ok(sg) => sg,
+            Err(e) => match e {},
+        }
     }
 
-    /// Iterate immutably over the Scene Graph in a depth first traversal.
-    pub fn iter_from_node(&self, node_index: NodeIndex) -> Result<SceneGraphIter<'_, T>, NodeDoesNotExist> {
-        let (parent_value, children) = match node_index {
-            NodeIndex::Root => (&self.root, self.root_children.as_ref()),
-            NodeIndex::Branch(idx) => {
-                let node = self.arena.get(idx).ok_or(NodeDoesNotExist)?;
+    /// Fallibly transforms every value in the graph, including the root, into a new SceneGraph with
+    /// the same shape and sibling order. The first `Err` returned by `f` is returned, and the rest of
+    /// the graph is dropped.
+    ///
+    /// Values are visited in a depth first traversal, starting with the root. The `NodeIndex`es in
+    /// the new graph will not match those of `self`.
+    pub fn try_map<U, E>(mut self, mut f: impl FnMut(T) -> Result<U, E>) -> Result<SceneGraph<U>, E> {
+        let mut new_sg = SceneGraph::new(f(self.root)?);
 
-                (&node.value, node.children.as_ref())
-            }
-        };
+        let mut helper_map = BTreeMap::new();
+        helper_map.insert(NodeIndex::Root, NodeIndex::Root);
+
+        for detached_node in SceneGraphDetachIter::new(&mut self.arena, NodeIndex::Root, self.root_children) {
+            let parent_place = helper_map[&detached_node.parent_idx];
+            let new_idx = new_sg.attach(parent_place, f(detached_node.node_value)?).unwrap();
+
+            helper_map.insert(detached_node.node_idx, new_idx);
+        }
 
-        Ok(SceneGraphIter::new(self, parent_value, children))
+        Ok(new_sg)
     }
 
-    /// Iterate immutably over the Scene Graph in a depth first traversal.
-    pub fn iter_mut_from_node(&mut self, node_index: NodeIndex) -> Result<SceneGraphIterMut<'_, T>, NodeDoesNotExist> {
-        match node_index {
-            NodeIndex::Root => {}
+    /// Moves a node from one parent to another parent. If this operation returns `Err`, then
+    /// nothing will have happened to the node.
+    ///
+    /// A node cannot be moved onto itself or onto one of its own descendants, as that would
+    /// create a cycle. In that case, [MoveError::WouldCreateCycle] is returned.
+    pub fn move_node(&mut self, moving_node_idx: NodeIndex, new_parent: NodeIndex) -> Result<(), MoveError> {
+        let moving_node_idx = match moving_node_idx {
+            NodeIndex::Root => return Err(MoveError::NodeDoesNotExist),
             NodeIndex::Branch(idx) => {
                 if !self.arena.contains(idx) {
-                    return Err(NodeDoesNotExist);
+                    return Err(MoveError::NodeDoesNotExist);
                 }
+
+                idx
             }
         };
 
-        Ok(SceneGraphIterMut::new(self, node_index))
+        if !self.contains(new_parent) {
+            return Err(MoveError::NodeDoesNotExist);
+        }
+
+        // a cycle which is already above the new parent would make the check below spin forever
+        debug_assert_eq!(
+            self.check_acyclic(new_parent),
+            Ok(()),
+            "cycle above the new parent in move_node"
+        );
+
+        // if we're above our new parent, we'd be moving into our own subtree
+        if new_parent == NodeIndex::Branch(moving_node_idx)
+            || self.is_ancestor_of(NodeIndex::Branch(moving_node_idx), new_parent)
+        {
+            return Err(MoveError::WouldCreateCycle);
+        }
+
+        // okay, now we hot swap em
+        debug_event!(
+            "moved {:?} from {:?} to {:?}",
+            moving_node_idx,
+            self.arena[moving_node_idx].parent,
+            new_parent
+        );
+        let old_parent = self.arena[moving_node_idx].parent;
+        self.unlink_node(moving_node_idx);
+        self.arena[moving_node_idx].parent = new_parent;
+
+        // place it!
+        self.place_node(new_parent, moving_node_idx)
+            .expect("we checked earlier");
+        debug_assert_eq!(self.check_links(old_parent), Ok(()), "broken links after move_node");
+        debug_assert_eq!(
+            self.check_links(NodeIndex::Branch(moving_node_idx)),
+            Ok(()),
+            "broken links after move_node"
+        );
+        debug_assert_eq!(
+            self.check_acyclic(NodeIndex::Branch(moving_node_idx)),
+            Ok(()),
+            "cycle above the moved node after move_node"
+        );
+        #[cfg(scene_graph_validate)]
+        debug_assert_eq!(self.validate(), Ok(()), "broken graph after move_node");
+
+        Ok(())
     }
 
-    /// Iterate while detaching over the Scene Graph in a depth first traversal.
+    /// Moves a node to be directly after `sibling`, under `sibling`'s parent. Like [move_node],
+    /// if this operation returns `Err`, then nothing will have happened to the node.
     ///
-    /// Note: the `root` will never be detached.
-    pub fn iter_detach_from_root(&mut self) -> SceneGraphDetachIter<'_, T> {
-        SceneGraphDetachIter::new(&mut self.arena, NodeIndex::Root, self.root_children.take())
+    /// [move_node]: Self::move_node
+    pub fn move_node_after(&mut self, moving_node_idx: NodeIndex, sibling: NodeIndex) -> Result<(), MoveError> {
+        let (moving_node_idx, sibling) = self.check_positional_move(moving_node_idx, sibling)?;
+        if moving_node_idx == sibling {
+            return Ok(());
+        }
+
+        self.unlink_node(moving_node_idx);
+        self.place_node_after(sibling, moving_node_idx);
+        debug_event!("moved {:?} after {:?}", moving_node_idx, sibling);
+
+        Ok(())
     }
 
-    /// Iterate while detaching over the Scene Graph in a depth first traversal.
-    /// This leaves the `node_index` given in the graph, but removes all its descendents.
-    pub fn iter_detach(&mut self, node_index: NodeIndex) -> Result<SceneGraphDetachIter<'_, T>, NodeDoesNotExist> {
-        let children = match node_index {
-            NodeIndex::Root => self.root_children.take(),
-            NodeIndex::Branch(br) => match self.arena.get_mut(br) {
-                Some(v) => v.children.take(),
-                None => return Err(NodeDoesNotExist),
-            },
+    /// Moves a node to be directly before `sibling`, under `sibling`'s parent. Like [move_node],
+    /// if this operation returns `Err`, then nothing will have happened to the node.
+    ///
+    /// [move_node]: Self::move_node
+    pub fn move_node_before(&mut self, moving_node_idx: NodeIndex, sibling: NodeIndex) -> Result<(), MoveError> {
+        let (moving_node_idx, sibling) = self.check_positional_move(moving_node_idx, sibling)?;
+        if moving_node_idx == sibling {
+            return Ok(());
+        }
+
+        self.unlink_node(moving_node_idx);
+        self.place_node_before(sibling, moving_node_idx);
+        debug_event!("moved {:?} before {:?}", moving_node_idx, sibling);
+
+        Ok(())
+    }
+
+    /// Moves a node up one level, so it becomes the next sibling of its current parent. Its children
+    /// come along with it.
+    ///
+    /// Note: promoting a direct child of the root does nothing and returns `Ok`, since there's no
+    /// higher level to move to. Trying to promote the root returns `Err`.
+    pub fn promote(&mut self, node_index: NodeIndex) -> Result<(), NodeDoesNotExist> {
+        let idx = match node_index {
+            NodeIndex::Root => return Err(NodeDoesNotExist),
+            NodeIndex::Branch(idx) => idx,
         };
 
-        Ok(SceneGraphDetachIter::new(&mut self.arena, node_index, children))
+        let node = self.arena.get(idx).ok_or(NodeDoesNotExist)?;
+        let NodeIndex::Branch(old_parent) = node.parent else { return Ok(()) };
+
+        self.unlink_node(idx);
+        self.place_node_after(old_parent, idx);
+        debug_event!("promoted {:?} out of {:?}", idx, old_parent);
+
+        Ok(())
     }
 
-    /// Iterate directly over only the *direct* children of `parent_index`.
+    /// Reverses the order of the *direct* children of `parent` in place. Grandchildren are not
+    /// reordered.
+    pub fn reverse_children(&mut self, parent: NodeIndex) -> Result<(), NodeDoesNotExist> {
+        let Some(children) = self.get_children_mut(parent)?.as_mut() else { return Ok(()) };
+        core::mem::swap(&mut children.first, &mut children.last);
+
+        let mut current_child = Some(children.first);
+        while let Some(idx) = current_child {
+            let node = &mut self.arena[idx];
+            core::mem::swap(&mut node.next_sibling, &mut node.last_sibling);
+
+            current_child = node.next_sibling;
+        }
+
+        Ok(())
+    }
+
+    /// Rotates the *direct* children of `parent` so the child at position `mid` comes first, and
+    /// the children before it move to the end, like [slice::rotate_left]. Each child's subtree
+    /// travels with it.
     ///
-    /// For example, given a graph:
-    /// ROOT:
-    ///     A
-    ///         B
-    ///         C
-    ///             D
-    /// using [iter_direct_children] and passing in the `parent_index` for `A` will only yield `B`
-    /// and `C`, *not* `D`. For that kind of depth first traversal, using `iter_on_node`.
+    /// A `mid` past the end wraps around modulo the number of children, so rotating by the number
+    /// of children does nothing. A parent without children is left alone.
+    pub fn rotate_children(&mut self, parent: NodeIndex, mid: usize) -> Result<(), NodeDoesNotExist> {
+        let mut children = self.collect_children(parent)?;
+        if children.is_empty() {
+            return Ok(());
+        }
+
+        let mid = mid % children.len();
+        children.rotate_left(mid);
+        self.relink_children(parent, &children);
+
+        Ok(())
+    }
+
+    /// Sorts the *direct* children of `parent` with a comparator on their values. Each child's
+    /// subtree travels with it, but is not itself sorted.
     ///
-    /// [iter_direct_children]: [Self::iter_direct_children]
-    pub fn iter_direct_children(
-        &self,
-        parent_index: NodeIndex,
-    ) -> Result<SceneGraphChildIter<'_, T>, NodeDoesNotExist> {
-        if let NodeIndex::Branch(idx) = parent_index {
-            self.arena.get(idx).ok_or(NodeDoesNotExist)?;
+    /// This sort is stable, so children which compare equal keep their order.
+    pub fn sort_children_by(
+        &mut self,
+        parent: NodeIndex,
+        mut cmp: impl FnMut(&T, &T) -> Ordering,
+    ) -> Result<(), NodeDoesNotExist> {
+        let mut children = self.collect_children(parent)?;
+        children.sort_by(|lhs, rhs| cmp(&self.arena[*lhs].value, &self.arena[*rhs].value));
+        self.relink_children(parent, &children);
+
+        Ok(())
+    }
+
+    /// Swaps the positions of two children of the same parent. Each child's subtree travels with
+    /// it. Swapping a node with itself does nothing.
+    ///
+    /// This operation is O(n) over the number of siblings.
+    pub fn swap_siblings(&mut self, a: NodeIndex, b: NodeIndex) -> Result<(), SiblingError> {
+        let (NodeIndex::Branch(a), NodeIndex::Branch(b)) = (a, b) else {
+            return Err(SiblingError::NodeDoesNotExist);
+        };
+        let a_parent = self.arena.get(a).ok_or(SiblingError::NodeDoesNotExist)?.parent;
+        let b_parent = self.arena.get(b).ok_or(SiblingError::NodeDoesNotExist)?.parent;
+        if a_parent != b_parent {
+            return Err(SiblingError::NotSiblings);
         }
 
-        Ok(SceneGraphChildIter::new(self, parent_index))
+        let mut children = self.collect_children(a_parent).expect("parent exists");
+        let a_pos = children.iter().position(|idx| *idx == a).unwrap();
+        let b_pos = children.iter().position(|idx| *idx == b).unwrap();
+        children.swap(a_pos, b_pos);
+        self.relink_children(a_parent, &children);
+
+        Ok(())
     }
 
-    /// Places a node as part of moving or attaching it.
-    fn place_node(&mut self, new_parent: NodeIndex, node_to_place: Index) -> Result<(), ParentNodeNotFound> {
-        // okay, now we gotta ATTACH ourselves back, without being monsters about it
-        let parent_children = match new_parent {
-            NodeIndex::Root => &mut self.root_children,
-            NodeIndex::Branch(idx) => &mut self.arena.get_mut(idx).ok_or(ParentNodeNotFound)?.children,
+    /// Swaps the positions of two nodes anywhere in the graph, so each one ends up with the other's
+    /// parent and place among its siblings. Each node's subtree travels with it. Swapping a node
+    /// with itself does nothing.
+    ///
+    /// A node can't be swapped with one of its own ancestors or descendants, since one would end up
+    /// inside of itself.
+    pub fn swap_nodes(&mut self, a: NodeIndex, b: NodeIndex) -> Result<(), SwapError> {
+        let (NodeIndex::Branch(a_idx), NodeIndex::Branch(b_idx)) = (a, b) else {
+            return Err(SwapError::NodeDoesNotExist);
         };
+        let a_node = self.arena.get(a_idx).ok_or(SwapError::NodeDoesNotExist)?;
+        let (a_parent, a_last_sibling) = (a_node.parent, a_node.last_sibling);
+        let b_node = self.arena.get(b_idx).ok_or(SwapError::NodeDoesNotExist)?;
+        let (b_parent, b_last_sibling) = (b_node.parent, b_node.last_sibling);
 
-        // slap ourselves in here
-        match parent_children.as_mut() {
-            Some(children) => {
-                let old_last = children.last;
-                children.last = node_to_place;
+        if a_idx == b_idx {
+            return Ok(());
+        }
+        if self.is_ancestor_of(a, b) || self.is_ancestor_of(b, a) {
+            return Err(SwapError::AncestorOfOther);
+        }
+        if a_parent == b_parent {
+            self.swap_siblings(a, b).expect("they're siblings");
+            return Ok(());
+        }
 
-                let mut last_sibling = &mut self.arena[old_last];
-                last_sibling.next_sibling = Some(node_to_place);
+        // with different parents, neither node can be the other's sibling, so both spots survive
+        // unlinking the two of them.
+        self.unlink_node(a_idx);
+        self.unlink_node(b_idx);
+        self.place_node_at(b_parent, b_last_sibling, a_idx);
+        self.place_node_at(a_parent, a_last_sibling, b_idx);
+        debug_event!("swapped {:?} with {:?}", a_idx, b_idx);
 
-                // fix this up too
-                self.arena[node_to_place].last_sibling = Some(old_last);
-            }
-            None => {
-                // this is the easy case
-                *parent_children = Some(Children {
-                    first: node_to_place,
-                    last: node_to_place,
-                });
-            }
+        Ok(())
+    }
+
+    /// Removes a node *without* returning anything. This can save a few allocations. This removes
+    /// all of its children as well.
+    ///
+    /// Note: removing a node which doesn't exist does nothing and returns `Ok`. Trying to remove
+    /// the Root returns `Err`.
+    pub fn remove(&mut self, node_index: NodeIndex) -> Result<(), CannotRemoveRoot> {
+        let index = match node_index {
+            NodeIndex::Root => return Err(CannotRemoveRoot),
+            NodeIndex::Branch(index) => index,
         };
 
+        let Some(node) = self.arena.remove(index) else { return Ok(()) };
+
+        // detach em all!
+        for _v in SceneGraphDetachIter::new(&mut self.arena, node_index, node.children) {}
+
+        self.fix_parent(node.next_sibling, node.last_sibling, node.parent, index);
+        debug_assert_eq!(self.check_links(node.parent), Ok(()), "broken links after remove");
+        #[cfg(scene_graph_validate)]
+        debug_assert_eq!(self.validate(), Ok(()), "broken graph after remove");
+
         Ok(())
     }
 
-    /// Fixes a parent with a removed child.
-    fn fix_parent(
-        &mut self,
-        removed_next_sibling: Option<Index>,
-        removed_last_sibling: Option<Index>,
-        removed_parent: NodeIndex,
-        removed_idx: Index,
-    ) {
-        // fix up the parent if it was the first child...
+    /// Removes every node, and all of its descendants, whose value fails `pred`. The root is always
+    /// kept.
+    ///
+    /// Nodes are visited in a depth first traversal, and the descendants of a removed node are
+    /// never passed to `pred`.
+    pub fn retain(&mut self, mut pred: impl FnMut(&T) -> bool) {
+        let mut pruned = Vec::new();
 
-        let mut parent_children = match removed_parent {
-            NodeIndex::Root => self.root_children.unwrap(),
-            NodeIndex::Branch(idx) => self.arena[idx].children.unwrap(),
-        };
+        let mut stacks = Vec::new();
+        if let Some(first_child) = self.root_children.map(|v| v.first) {
+            stacks.push(first_child);
+        }
 
-        if parent_children.first == parent_children.last && parent_children.first == removed_idx {
-            match removed_parent {
-                NodeIndex::Root => self.root_children = None,
-                NodeIndex::Branch(idx) => self.arena[idx].children = None,
-            };
-        } else {
-            // extremely hard to follow the logic of this unwrap here, but if this branch is taken,
-            // then we're *never* the last child, which means we have a sibling.
-            if parent_children.first == removed_idx {
-                parent_children.first = removed_next_sibling.unwrap();
+        while let Some(idx) = stacks.pop() {
+            let node = &self.arena[idx];
+            if let Some(next_sibling) = node.next_sibling {
+                stacks.push(next_sibling);
             }
 
-            if parent_children.last == removed_idx {
-                parent_children.last = removed_last_sibling.unwrap();
+            if !pred(&node.value) {
+                pruned.push(idx);
+                continue;
             }
 
-            if let Some(last_sibling) = removed_last_sibling {
-                let last_sibling = self.arena.get_mut(last_sibling).unwrap();
-                last_sibling.next_sibling = removed_next_sibling;
+            if let Some(first_child) = node.children.map(|v| v.first) {
+                stacks.push(first_child);
             }
+        }
 
-            if let Some(next_sibling) = removed_next_sibling {
-                let next_sibling = self.arena.get_mut(next_sibling).unwrap();
-                next_sibling.last_sibling = removed_last_sibling;
+        for idx in pruned {
+            self.remove(NodeIndex::Branch(idx)).unwrap();
+        }
+    }
+
+    /// Removes every node without children whose value passes `pred`, over and over, so a node whose
+    /// children all get pruned is considered too. This is handy for cleaning up containers which
+    /// mean nothing once they're empty. The root is always kept.
+    ///
+    /// Nodes are visited bottom up, each exactly once, so this is a single O(n) pass.
+    pub fn prune_where_childless(&mut self, mut pred: impl FnMut(&T) -> bool) {
+        // a node's descendants all come after it in a depth first traversal, so walking it
+        // backwards settles every child before its parent.
+        for node_index in self.collect_indices_dfs().into_iter().rev() {
+            let node = self.get(node_index).expect("only leaves are removed");
+
+            if !node.has_children() && pred(&node.value) {
+                self.remove(node_index).unwrap();
             }
+        }
+    }
 
-            // finally, dump our updated parent children back
-            match removed_parent {
-                NodeIndex::Root => self.root_children = Some(parent_children),
-                NodeIndex::Branch(idx) => self.arena[idx].children = Some(parent_children),
-            };
+    /// Removes every node deeper than `max_depth`, so that afterwards the [height] of the graph is
+    /// at most `max_depth`. Nodes at exactly `max_depth` are kept, but lose all their children, and
+    /// a `max_depth` of `0` removes every node but the root.
+    ///
+    /// [height]: Self::height
+    pub fn trim_to_depth(&mut self, max_depth: usize) {
+        let mut stacks = vec![(NodeIndex::Root, 0)];
+
+        while let Some((node_index, depth)) = stacks.pop() {
+            if depth == max_depth {
+                // each node on the stack is in its own subtree, so this doesn't touch them
+                for _v in self.iter_detach(node_index).unwrap() {}
+                continue;
+            }
+
+            let mut current_child = self.get_children(node_index).unwrap().map(|v| v.first);
+            while let Some(idx) = current_child {
+                stacks.push((NodeIndex::Branch(idx), depth + 1));
+                current_child = self.arena[idx].next_sibling;
+            }
         }
     }
-}
 
-impl<'a, T> IntoIterator for &'a SceneGraph<T> {
-    type Item = (&'a T, &'a T);
+    /// Returns the number of *direct* children of the given node.
+    ///
+    /// This operation is O1 over the number of children. Note: this returns `0` if the node
+    /// doesn't exist.
+    pub fn num_children(&self, node_index: NodeIndex) -> usize {
+        self.get_children(node_index)
+            .ok()
+            .flatten()
+            .map(|v| v.count)
+            .unwrap_or_default()
+    }
 
-    type IntoIter = SceneGraphIter<'a, T>;
+    /// Returns the first *direct* child of the given node.
+    ///
+    /// This operation is O1. Returns `None` if the node has no children or doesn't exist.
+    pub fn first_child(&self, node_index: NodeIndex) -> Option<NodeIndex> {
+        let children = self.get_children(node_index).ok()??;
 
-    fn into_iter(self) -> Self::IntoIter {
-        self.iter()
+        Some(NodeIndex::Branch(children.first))
     }
-}
 
-impl<'a, T> IntoIterator for &'a mut SceneGraph<T> {
-    type Item = (&'a mut T, &'a mut T);
+    /// Returns the last *direct* child of the given node.
+    ///
+    /// This operation is O1. Returns `None` if the node has no children or doesn't exist.
+    pub fn last_child(&self, node_index: NodeIndex) -> Option<NodeIndex> {
+        let children = self.get_children(node_index).ok()??;
 
-    type IntoIter = SceneGraphIterMut<'a, T>;
+        Some(NodeIndex::Branch(children.last))
+    }
 
-    fn into_iter(self) -> Self::IntoIter {
-        self.iter_mut()
+    /// Returns the first and last *direct* children of the given node, which are the same node if it
+    /// only has one child. Useful for building custom traversals with [Node::next_sibling].
+    ///
+    /// This operation is O1. Returns `None` if the node has no children or doesn't exist.
+    pub fn children_bounds(&self, node_index: NodeIndex) -> Option<(NodeIndex, NodeIndex)> {
+        let children = self.get_children(node_index).ok()??;
+
+        Some((NodeIndex::Branch(children.first), NodeIndex::Branch(children.last)))
     }
-}
 
-/// A wrapper around the values given to the SceneGraph. This struct includes the data on the
-/// relationships to other nodes, in addition to the value placed at the node.
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
-pub struct Node<T> {
-    /// The value contained within the node.
-    pub value: T,
-    parent: NodeIndex,
-    children: Option<Children>,
-    last_sibling: Option<Index>,
-    next_sibling: Option<Index>,
-}
+    /// Returns the first leaf under the given node in a depth first traversal, found by following
+    /// each first child down until there are no more. A node without children is its own first
+    /// leaf, which includes the Root of an empty graph.
+    ///
+    /// This operation is O(n) over the height of the subtree. Returns `None` if the node doesn't
+    /// exist.
+    pub fn first_leaf(&self, node_index: NodeIndex) -> Option<NodeIndex> {
+        let mut current = node_index;
+        while let Some(children) = self.get_children(current).ok()? {
+            current = NodeIndex::Branch(children.first);
+        }
 
-impl<T> Node<T> {
-    fn new(value: T, parent: NodeIndex) -> Self {
-        Self {
-            value,
-            parent,
-            last_sibling: None,
-            next_sibling: None,
-            children: None,
+        Some(current)
+    }
+
+    /// Returns the last leaf under the given node in a depth first traversal, found by following
+    /// each last child down. Otherwise, this is the same as [first_leaf].
+    ///
+    /// [first_leaf]: Self::first_leaf
+    pub fn last_leaf(&self, node_index: NodeIndex) -> Option<NodeIndex> {
+        let mut current = node_index;
+        while let Some(children) = self.get_children(current).ok()? {
+            current = NodeIndex::Branch(children.last);
         }
+
+        Some(current)
     }
 
-    /// Returns true if this node has children.
-    pub fn has_children(&self) -> bool {
-        self.children.is_some()
+    /// Returns the node which comes after the given node in a depth first traversal, which is its
+    /// first child, or else its next sibling, or else the next sibling of its nearest ancestor which
+    /// has one. The Root comes before every other node, so this steps through the same order as
+    /// `iter`, one node at a time.
+    ///
+    /// Returns `None` after the last node, or if the node doesn't exist.
+    pub fn next_in_order(&self, node_index: NodeIndex) -> Option<NodeIndex> {
+        let mut current = match node_index {
+            NodeIndex::Root => return self.first_child(NodeIndex::Root),
+            NodeIndex::Branch(idx) => self.arena.get(idx)?,
+        };
+
+        if let Some(children) = current.children {
+            return Some(NodeIndex::Branch(children.first));
+        }
+
+        loop {
+            if let Some(next_sibling) = current.next_sibling {
+                return Some(NodeIndex::Branch(next_sibling));
+            }
+
+            match current.parent {
+                NodeIndex::Root => return None,
+                NodeIndex::Branch(parent) => current = &self.arena[parent],
+            }
+        }
     }
 
-    /// Iterate directly over only the *direct* children of `parent_index`.
+    /// Returns the node which comes before the given node in a depth first traversal, which is the
+    /// [last_leaf] of its previous sibling, or else its parent. This is the reverse of
+    /// [next_in_order].
     ///
-    /// For example, given a graph:
-    /// ROOT:
-    ///     A
-    ///         B
-    ///         C
-    ///             D
-    /// using `iter_direct_children` and passing in the `parent_index` for `A` will only yield `B`
-    /// and `C`, *not* `D`. For that kind of depth first traversal, using `iter_on_node`.
+    /// Returns `None` for the Root, which is always first, or if the node doesn't exist.
     ///
-    /// Note: passing in a SceneGraph of a different kind than this node belongs to (but of the same
-    /// type) will create logic errors or panics.
-    pub fn iter_direct_children<'a>(&'a self, sg: &'a SceneGraph<T>) -> SceneGraphChildIter<'a, T> {
-        SceneGraphChildIter::with_children(sg, self.children.as_ref())
+    /// [last_leaf]: Self::last_leaf
+    /// [next_in_order]: Self::next_in_order
+    pub fn prev_in_order(&self, node_index: NodeIndex) -> Option<NodeIndex> {
+        let NodeIndex::Branch(idx) = node_index else { return None };
+        let node = self.arena.get(idx)?;
+
+        match node.last_sibling {
+            Some(last_sibling) => self.last_leaf(NodeIndex::Branch(last_sibling)),
+            None => Some(node.parent),
+        }
+    }
+
+    /// Returns the position of a node among its siblings, counting from `0`, so the first child of
+    /// a parent returns `Some(0)`. This is the opposite of [child_at].
+    ///
+    /// This operation is O(n) over the position of the node. Returns `None` for the root or if the
+    /// node doesn't exist.
+    ///
+    /// [child_at]: Self::child_at
+    pub fn sibling_index(&self, node_index: NodeIndex) -> Option<usize> {
+        let mut last_sibling = self.get(node_index)?.last_sibling;
+
+        let mut position = 0;
+        while let Some(idx) = last_sibling {
+            position += 1;
+            last_sibling = self.arena[idx].last_sibling;
+        }
+
+        Some(position)
+    }
+
+    /// Returns the `n`th *direct* child of the given node, counting from `0`.
+    ///
+    /// This operation is O(n) over `n`. Returns `None` if `n` is out of range or if the node doesn't
+    /// exist.
+    pub fn child_at(&self, parent: NodeIndex, n: usize) -> Option<NodeIndex> {
+        let children = self.get_children(parent).ok()??;
+        if n >= children.count {
+            return None;
+        }
+
+        let mut current_child = children.first;
+        for _ in 0..n {
+            current_child = self.arena[current_child].next_sibling?;
+        }
+
+        Some(NodeIndex::Branch(current_child))
+    }
+
+    /// Returns `true` is the given `node_index` is valid.
+    pub fn contains(&self, node_index: NodeIndex) -> bool {
+        match node_index {
+            NodeIndex::Root => true,
+            NodeIndex::Branch(idx) => self.arena.contains(idx),
+        }
+    }
+
+    /// Gets a given node based on `NodeIndex`. Note that the `Root` always returns `None`, as it is
+    /// not a true node. Use `root` to get the root value.
+    pub fn get(&self, node_index: NodeIndex) -> Option<&Node<T>> {
+        match node_index {
+            NodeIndex::Root => None,
+            NodeIndex::Branch(idx) => self.arena.get(idx),
+        }
     }
 
-    /// Returns the index of the parent.
-    pub fn parent(&self) -> NodeIndex {
-        self.parent
-    }
-}
+    /// Gets a given node based on `NodeIndex`. Note that the `Root` always returns `None`,
+    /// as it is not a true node. Use `get_children` to generically get children.
+    pub fn get_mut(&mut self, node_index: NodeIndex) -> Option<&mut Node<T>> {
+        match node_index {
+            NodeIndex::Root => None,
+            NodeIndex::Branch(idx) => self.arena.get_mut(idx),
+        }
+    }
+
+    /// Gets the value of a given node. Unlike `get`, this works for the `Root` too, returning the
+    /// root value.
+    pub fn get_value(&self, node_index: NodeIndex) -> Option<&T> {
+        match node_index {
+            NodeIndex::Root => Some(&self.root),
+            NodeIndex::Branch(idx) => self.arena.get(idx).map(|node| &node.value),
+        }
+    }
+
+    /// Gets the value of a given node mutably. Unlike `get_mut`, this works for the `Root` too,
+    /// returning the root value.
+    pub fn get_value_mut(&mut self, node_index: NodeIndex) -> Option<&mut T> {
+        match node_index {
+            NodeIndex::Root => Some(&mut self.root),
+            NodeIndex::Branch(idx) => self.arena.get_mut(idx).map(|node| &mut node.value),
+        }
+    }
+
+    /// Gets mutable references to the values of several nodes at once. Returns `None` if any of the
+    /// nodes doesn't exist, is the `Root`, or is given more than once.
+    ///
+    /// This operation is O(N log N) over `N`, and looks up only the given nodes, so it doesn't
+    /// depend on the size of the graph.
+    pub fn get_many_mut<const N: usize>(&mut self, indices: [NodeIndex; N]) -> Option<[&mut T; N]> {
+        let mut slots = [0; N];
+        for (slot, node_index) in slots.iter_mut().zip(indices) {
+            let NodeIndex::Branch(idx) = node_index else { return None };
+            if !self.arena.contains(idx) {
+                return None;
+            }
+            *slot = idx.slot();
+        }
+
+        // a live node is the only one in its slot, so two live indices share a slot only if they're
+        // the same node
+        slots.sort_unstable();
+        if slots.windows(2).any(|pair| pair[0] == pair[1]) {
+            return None;
+        }
+
+        let values = indices.map(|node_index| {
+            let NodeIndex::Branch(idx) = node_index else { unreachable!() };
+            &mut self.arena[idx].value as *mut T
+        });
+
+        // safety: every index exists and is in a distinct slot, so each pointer is to a distinct
+        // value in the arena, and they all live as long as our borrow of `self`. like `iter_mut`,
+        // this only holds up under miri's Tree Borrows.
+        Some(values.map(|value| unsafe { &mut *value }))
+    }
+
+    /// Gets the root node's value.
+    pub fn root(&self) -> &T {
+        &self.root
+    }
+
+    /// Gets the root node's value mutably.
+    pub fn root_mut(&mut self) -> &mut T {
+        &mut self.root
+    }
+
+    /// Replaces the root node's value, returning the old one. Its children stay attached.
+    pub fn set_root(&mut self, value: T) -> T {
+        core::mem::replace(&mut self.root, value)
+    }
+
+    /// Returns the index of the root, which is always `NodeIndex::Root`. This is handy where a
+    /// method is a more natural fit than the variant, such as in a chain of calls.
+    pub fn root_index(&self) -> NodeIndex {
+        NodeIndex::Root
+    }
+
+    /// Returns true if the root has any children. This is the same as `!is_empty()`, and mirrors
+    /// [Node::has_children].
+    pub fn root_has_children(&self) -> bool {
+        self.root_children.is_some()
+    }
+
+    /// Iterate over the *direct* children of the root. This is a shortcut for
+    /// `iter_direct_children(NodeIndex::Root)`, which can't fail.
+    pub fn root_children_iter(&self) -> SceneGraphChildIter<'_, T> {
+        SceneGraphChildIter::new(self, NodeIndex::Root)
+    }
+
+    /// Replaces the value of a node, returning the old value. Replacing the `Root` returns the old
+    /// root value. Returns `None`, and drops `value`, if the node doesn't exist.
+    pub fn replace_value(&mut self, node_index: NodeIndex, value: T) -> Option<T> {
+        let old_value = match node_index {
+            NodeIndex::Root => &mut self.root,
+            NodeIndex::Branch(idx) => &mut self.arena.get_mut(idx)?.value,
+        };
+
+        Some(core::mem::replace(old_value, value))
+    }
+
+    /// Swaps the values of two nodes, leaving the shape of the graph untouched. Either node can be
+    /// the `Root`. Swapping a node with itself does nothing.
+    pub fn swap_values(&mut self, a: NodeIndex, b: NodeIndex) -> Result<(), NodeDoesNotExist> {
+        match (a, b) {
+            (NodeIndex::Root, NodeIndex::Root) => {}
+            (NodeIndex::Root, NodeIndex::Branch(idx)) | (NodeIndex::Branch(idx), NodeIndex::Root) => {
+                let node = self.arena.get_mut(idx).ok_or(NodeDoesNotExist)?;
+                core::mem::swap(&mut self.root, &mut node.value);
+            }
+            (NodeIndex::Branch(a), NodeIndex::Branch(b)) => {
+                if a == b {
+                    return if self.arena.contains(a) { Ok(()) } else { Err(NodeDoesNotExist) };
+                }
+
+                match self.arena.get2_mut(a, b) {
+                    (Some(a), Some(b)) => core::mem::swap(&mut a.value, &mut b.value),
+                    _ => return Err(NodeDoesNotExist),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the parent NodeIndex of a given Node.
+    ///
+    /// This operation is O1 over the number of nodes in the SceneGraph.
+    /// Note: this returns `None` for the Root, which has no parent, and for nodes which don't exist.
+    pub fn parent(&self, node_index: NodeIndex) -> Option<NodeIndex> {
+        self.get(node_index).map(|v| v.parent)
+    }
+
+    /// Returns the depth of a given Node, which is its distance from the root. The root has a depth
+    /// of `0`, and its direct children have a depth of `1`.
+    ///
+    /// This operation is O(n) over the depth of the node. Returns `None` if the node doesn't exist.
+    pub fn depth(&self, node_index: NodeIndex) -> Option<usize> {
+        self.iter_ancestors(node_index).ok().map(|ancestors| ancestors.count())
+    }
+
+    /// Returns the number of ancestors a node has, counting the root. This is always the same as
+    /// [depth], but can read better in layout code.
+    ///
+    /// [depth]: Self::depth
+    pub fn ancestor_count(&self, node_index: NodeIndex) -> Option<usize> {
+        self.depth(node_index)
+    }
+
+    /// Returns `true` if the node's parent is the root.
+    ///
+    /// This operation is O1. Returns `false` for the Root itself, and for nodes which don't exist.
+    pub fn is_direct_child_of_root(&self, node_index: NodeIndex) -> bool {
+        self.parent(node_index) == Some(NodeIndex::Root)
+    }
+
+    /// Returns the height of the graph, which is the depth of its deepest node. A graph with only a
+    /// root has a height of `0`.
+    ///
+    /// This operation is O(n) over the number of nodes in the SceneGraph.
+    pub fn height(&self) -> usize {
+        self.iter_with_depth().map(|(depth, _)| depth).max().unwrap_or_default()
+    }
+
+    /// Returns the number of nodes at the given depth. Like [depth], the root is the only node at
+    /// depth `0`, so that always returns `1`, and its children are at depth `1`.
+    ///
+    /// This operation is O(n) over the number of nodes in the SceneGraph.
+    ///
+    /// [depth]: Self::depth
+    pub fn count_at_depth(&self, depth: usize) -> usize {
+        if depth == 0 {
+            return 1;
+        }
+
+        self.iter_with_depth().filter(|(node_depth, _)| *node_depth == depth).count()
+    }
+
+    /// Returns the number of nodes at every depth, so that the count at index `d` is the same as
+    /// `count_at_depth(d)`. The root is counted at index `0`, so this is never empty, and its
+    /// length is always `height() + 1`.
+    ///
+    /// This operation is O(n) over the number of nodes in the SceneGraph.
+    pub fn depth_histogram(&self) -> Vec<usize> {
+        let mut histogram = vec![1];
+
+        for (depth, _) in self.iter_with_depth() {
+            if depth == histogram.len() {
+                histogram.push(0);
+            }
+            histogram[depth] += 1;
+        }
+
+        histogram
+    }
+
+    /// Returns the number of nodes in the subtree starting at the given node, *including* the node
+    /// itself. Since the root is included too, `NodeIndex::Root` returns `len() + 1`.
+    ///
+    /// This operation is O(n) over the size of the subtree. Returns `None` if the node doesn't exist.
+    pub fn subtree_size(&self, node_index: NodeIndex) -> Option<usize> {
+        match node_index {
+            NodeIndex::Root => Some(self.len() + 1),
+            NodeIndex::Branch(_) => self.iter_from_node(node_index).ok().map(|iter| iter.count() + 1),
+        }
+    }
+
+    /// Returns the number of leaves in the graph, which are nodes without children.
+    ///
+    /// Note: the root is never counted, so a graph with only a root has `0` leaves, just like it
+    /// has a `len` of `0`.
+    pub fn count_leaves(&self) -> usize {
+        self.arena.iter().filter(|(_, node)| !node.has_children()).count()
+    }
+
+    /// Returns the first node, in a depth first traversal, whose value satisfies `pred`.
+    ///
+    /// The root is checked first, so this returns `NodeIndex::Root` if the root value satisfies
+    /// `pred`.
+    pub fn find(&self, pred: impl Fn(&T) -> bool) -> Option<NodeIndex> {
+        self.iter_indices_with_root()
+            .find(|(_, value)| pred(value))
+            .map(|(idx, _)| idx)
+    }
+
+    /// Returns every node, in a depth first traversal, whose value satisfies `pred`.
+    ///
+    /// The root is checked first, so the first element will be `NodeIndex::Root` if the root value
+    /// satisfies `pred`.
+    pub fn find_all(&self, pred: impl Fn(&T) -> bool) -> Vec<NodeIndex> {
+        self.iter_indices_with_root()
+            .filter(|(_, value)| pred(value))
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// Returns `true` if any value in the graph, *including* the root, equals `value`.
+    ///
+    /// This operation is O(n) over the number of nodes in the SceneGraph.
+    pub fn contains_value(&self, value: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.root == *value || self.iter().any(|(_, v)| v == value)
+    }
+
+    /// Folds every value in the graph, *including* the root, into an accumulator in a depth first
+    /// traversal. The root is always folded first.
+    pub fn fold<B>(&self, init: B, mut f: impl FnMut(B, &T) -> B) -> B {
+        self.iter_indices_with_root().fold(init, |acc, (_, value)| f(acc, value))
+    }
+
+    /// Returns `true` if `ancestor` is the parent of `descendant`, or the parent of that parent,
+    /// and so on up to the root.
+    ///
+    /// `NodeIndex::Root` is an ancestor of every other node. A node is never its own ancestor, and
+    /// this returns `false` if either node doesn't exist.
+    pub fn is_ancestor_of(&self, ancestor: NodeIndex, descendant: NodeIndex) -> bool {
+        if !self.contains(ancestor) {
+            return false;
+        }
+
+        match self.iter_ancestors(descendant) {
+            Ok(mut ancestors) => ancestors.any(|(idx, _)| idx == ancestor),
+            Err(_) => false,
+        }
+    }
+
+    /// Returns `true` if `node` is `ancestor` itself or anywhere in its subtree. This is the same as
+    /// [is_ancestor_of], except that a node's subtree contains the node.
+    ///
+    /// This operation is O(n) over the depth of `node`. Returns `false` if either node doesn't exist.
+    ///
+    /// [is_ancestor_of]: Self::is_ancestor_of
+    pub fn subtree_contains(&self, ancestor: NodeIndex, node: NodeIndex) -> bool {
+        if ancestor == node {
+            return self.contains(node);
+        }
+
+        self.is_ancestor_of(ancestor, node)
+    }
+
+    /// Returns `true` if `descendant` is a child of `ancestor`, or a child of one of its children,
+    /// and so on. This is the mirror of [is_ancestor_of].
+    ///
+    /// [is_ancestor_of]: Self::is_ancestor_of
+    pub fn is_descendant_of(&self, descendant: NodeIndex, ancestor: NodeIndex) -> bool {
+        self.is_ancestor_of(ancestor, descendant)
+    }
+
+    /// Returns the nearest node which is an ancestor of both `a` and `b`, which may be the Root.
+    ///
+    /// If one node is an ancestor of the other, then that node is returned, and if `a` and `b` are
+    /// the same node, it is returned. This returns `None` only if either node doesn't exist.
+    pub fn common_ancestor(&self, a: NodeIndex, b: NodeIndex) -> Option<NodeIndex> {
+        let a_ancestors = self.iter_ancestors(a).ok()?;
+        let b_ancestors = self.iter_ancestors(b).ok()?;
+
+        let a_chain: BTreeSet<NodeIndex> = core::iter::once(a).chain(a_ancestors.map(|(idx, _)| idx)).collect();
+
+        core::iter::once(b)
+            .chain(b_ancestors.map(|(idx, _)| idx))
+            .find(|idx| a_chain.contains(idx))
+    }
+
+    /// Returns the path from `node_index` up to the root, starting with `node_index` itself and
+    /// ending with `NodeIndex::Root`.
+    ///
+    /// For the Root, this returns `vec![NodeIndex::Root]`. Returns `None` if the node doesn't exist.
+    pub fn path_to_root(&self, node_index: NodeIndex) -> Option<Vec<NodeIndex>> {
+        let ancestors = self.iter_ancestors(node_index).ok()?;
+
+        Some(core::iter::once(node_index).chain(ancestors.map(|(idx, _)| idx)).collect())
+    }
+
+    /// Returns the path between two nodes, going through their [common_ancestor]. The first list is
+    /// the way up, starting at `a`, and the second is the way back down, ending at `b`. Neither
+    /// list includes the common ancestor itself, so if `a` is an ancestor of `b`, the first list is
+    /// empty, and vice versa.
+    ///
+    /// Returns `None` if either node doesn't exist.
+    ///
+    /// [common_ancestor]: Self::common_ancestor
+    pub fn relative_path(&self, a: NodeIndex, b: NodeIndex) -> Option<(Vec<NodeIndex>, Vec<NodeIndex>)> {
+        let common_ancestor = self.common_ancestor(a, b)?;
+
+        let path_until_common = |node_index: NodeIndex| -> Vec<NodeIndex> {
+            core::iter::once(node_index)
+                .chain(self.iter_ancestors(node_index).unwrap().map(|(idx, _)| idx))
+                .take_while(|idx| *idx != common_ancestor)
+                .collect()
+        };
+
+        let up = path_until_common(a);
+        let mut down = path_until_common(b);
+        down.reverse();
+
+        Some((up, down))
+    }
+
+    /// Checks that the internal links of the graph are consistent, returning the first problem
+    /// found. A graph which was only ever changed through this API should always be valid, so this
+    /// is mostly useful for hunting down bugs.
+    ///
+    /// This operation is O(n) over the number of nodes in the SceneGraph.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        for (idx, node) in self.arena.iter() {
+            if !self.contains(node.parent) {
+                return Err(ValidationError::DanglingParent(NodeIndex::Branch(idx)));
+            }
+        }
+
+        let mut visited = BTreeSet::new();
+        let mut parents = vec![NodeIndex::Root];
+
+        while let Some(parent) = parents.pop() {
+            let Some(children) = self.get_children(parent).expect("parents are visited nodes") else { continue };
+
+            let mut count = 0;
+            let mut last_sibling = None;
+            let mut current_child = Some(children.first);
+
+            while let Some(idx) = current_child {
+                let node_index = NodeIndex::Branch(idx);
+                let node = self.arena.get(idx).ok_or(ValidationError::DanglingChild(parent))?;
+
+                if !visited.insert(idx) {
+                    return Err(ValidationError::VisitedTwice(node_index));
+                }
+                if node.parent != parent {
+                    return Err(ValidationError::WrongParent(node_index));
+                }
+                if node.last_sibling != last_sibling {
+                    return Err(ValidationError::WrongPreviousSibling(node_index));
+                }
+
+                count += 1;
+                parents.push(node_index);
+                last_sibling = Some(idx);
+                current_child = node.next_sibling;
+            }
+
+            if last_sibling != Some(children.last) {
+                return Err(ValidationError::WrongLastChild(parent));
+            }
+            if count != children.count {
+                return Err(ValidationError::WrongChildCount(parent));
+            }
+        }
+
+        if let Some((idx, _)) = self.arena.iter().find(|(idx, _)| !visited.contains(idx)) {
+            return Err(ValidationError::Unreachable(NodeIndex::Branch(idx)));
+        }
+
+        Ok(())
+    }
+
+    /// Iterate mutably over the Scene Graph in a depth first traversal. The root isn't yielded, and
+    /// neither are parents, so use [for_each_mut] if you need each node's parent while mutating it.
+    ///
+    /// [for_each_mut]: Self::for_each_mut
+    pub fn iter_mut(&mut self) -> SceneGraphIterMut<'_, T> {
+        SceneGraphIterMut::new(self, NodeIndex::Root)
+    }
+
+    /// Calls `f` on every value in the Scene Graph in a depth first traversal, passing in the value
+    /// of its parent alongside it. The root comes first, with no parent. Since parents are always
+    /// visited before their children, each child sees its parent's updated value, which is exactly
+    /// what resolving local transforms into world transforms needs.
+    ///
+    /// Unlike [iter_mut], this hands out each parent, which is only borrowed for the length of each
+    /// call, so it can't be misused to hold onto two references to the same value.
+    ///
+    /// [iter_mut]: Self::iter_mut
+    pub fn for_each_mut(&mut self, mut f: impl FnMut(Option<&T>, &mut T)) {
+        f(None, &mut self.root);
+
+        let mut stacks: Stack<(NodeIndex, Index)> = Stack::new();
+        if let Some(children) = self.root_children {
+            stacks.push((NodeIndex::Root, children.first));
+        }
+
+        while let Some((parent, idx)) = stacks.pop() {
+            let (parent_value, current_child) = match parent {
+                NodeIndex::Root => (&self.root, &mut self.arena[idx]),
+                NodeIndex::Branch(parent) => {
+                    let (parent, current_child) = self.arena.get2_mut(parent, idx);
+
+                    (&parent.unwrap().value, current_child.unwrap())
+                }
+            };
+
+            f(Some(parent_value), &mut current_child.value);
+
+            if let Some(next_sibling) = current_child.next_sibling {
+                stacks.push((parent, next_sibling));
+            }
+            if let Some(children) = current_child.children {
+                stacks.push((NodeIndex::Branch(idx), children.first));
+            }
+        }
+    }
+
+    /// Iterate immutably over the Scene Graph in a depth first traversal.
+    pub fn iter(&self) -> SceneGraphIter<'_, T> {
+        self.iter_from_node(NodeIndex::Root).unwrap()
+    }
+
+    /// Iterate immutably over the Scene Graph in a depth first traversal, yielding the index of
+    /// each node's parent, the index of the node, and its value.
+    pub fn iter_with_indices(&self) -> SceneGraphIndexedIter<'_, T> {
+        SceneGraphIndexedIter::new(self.iter())
+    }
+
+    /// Iterate over every edge in the Scene Graph as a `(parent, child)` pair of indices, in a depth
+    /// first traversal. Edges from the Root are included, so there's exactly one edge per node
+    /// other than the root. This is handy for exporting the graph's shape somewhere else.
+    pub fn iter_edges(&self) -> impl Iterator<Item = (NodeIndex, NodeIndex)> + '_ {
+        self.iter_with_indices().map(|(parent, idx, _)| (parent, idx))
+    }
+
+    /// Collects the index of every node but the root, in a depth first traversal. This is a
+    /// snapshot, so it's handy for looping over nodes while changing the graph, since the indices
+    /// stay usable as long as their nodes aren't removed.
+    pub fn collect_indices_dfs(&self) -> Vec<NodeIndex> {
+        let mut indices = Vec::with_capacity(self.len());
+        indices.extend(self.iter_with_indices().map(|(_, idx, _)| idx));
+
+        indices
+    }
+
+    /// Iterate immutably over the Scene Graph in a depth first traversal, just like [iter], but
+    /// keeping the traversal stack in `scratch`. Reusing the same `scratch` every frame means the
+    /// stack only allocates until it has grown to fit the graph.
+    ///
+    /// Anything left in `scratch` from a previous iteration is cleared first.
+    ///
+    /// [iter]: Self::iter
+    pub fn iter_with_buffer<'a>(&'a self, scratch: &'a mut TraversalScratch) -> SceneGraphBufferedIter<'a, T> {
+        SceneGraphBufferedIter::new(self, scratch)
+    }
+
+    /// Iterate immutably over the Scene Graph in a depth first traversal, yielding the depth of each
+    /// node alongside its value. The root's children are at depth 1.
+    pub fn iter_with_depth(&self) -> SceneGraphDepthIter<'_, T> {
+        SceneGraphDepthIter::new(self.iter())
+    }
+
+    /// Iterate mutably over the Scene Graph in a depth first traversal, yielding the index of
+    /// each node's parent, the index of the node, and its value.
+    pub fn iter_mut_with_indices(&mut self) -> SceneGraphIndexedIterMut<'_, T> {
+        SceneGraphIndexedIterMut::new(self.iter_mut())
+    }
+
+    /// Iterate immutably over only the leaves of the Scene Graph, which are nodes without children,
+    /// in a depth first traversal. Like [count_leaves], the root is never yielded.
+    ///
+    /// [count_leaves]: Self::count_leaves
+    pub fn iter_leaves(&self) -> impl Iterator<Item = (NodeIndex, &T)> {
+        self.iter_with_indices()
+            .filter(|(_, idx, _)| !self.get(*idx).unwrap().has_children())
+            .map(|(_, idx, value)| (idx, value))
+    }
+
+    /// Iterate immutably over the Scene Graph out of order. This is useful for speed.
+    ///
+    /// Note: this never yields the root, since it's not stored with the other nodes. Use
+    /// [iter_all_out_of_order] to include it.
+    ///
+    /// [iter_all_out_of_order]: Self::iter_all_out_of_order
+    pub fn iter_out_of_order(&self) -> impl Iterator<Item = (NodeIndex, &T)> {
+        self.arena.iter().map(|(k, v)| (NodeIndex::Branch(k), &v.value))
+    }
+
+    /// Iterate immutably over every value in the Scene Graph out of order, *including* the root,
+    /// which is always yielded first. Otherwise, this is the same as [iter_out_of_order].
+    ///
+    /// [iter_out_of_order]: Self::iter_out_of_order
+    pub fn iter_all_out_of_order(&self) -> impl Iterator<Item = (NodeIndex, &T)> {
+        core::iter::once((NodeIndex::Root, &self.root)).chain(self.iter_out_of_order())
+    }
+
+    /// Iterate immutably over the descendants of `node_index`, in no particular order. This is
+    /// useful for scanning a subtree quickly when [iter_from_node] would do more work than needed,
+    /// since it doesn't keep track of each node's parent. The node itself is never yielded.
+    ///
+    /// Note: this yields nothing if the node doesn't exist.
+    ///
+    /// [iter_from_node]: Self::iter_from_node
+    pub fn descendants_out_of_order(&self, node_index: NodeIndex) -> impl Iterator<Item = (NodeIndex, &T)> {
+        let mut stacks: Stack<Index> = Stack::new();
+        if let Some(children) = self.get_children(node_index).ok().flatten() {
+            stacks.push(children.first);
+        }
+
+        core::iter::from_fn(move || {
+            let idx = stacks.pop()?;
+            let node = &self.arena[idx];
+
+            if let Some(next_sibling) = node.next_sibling {
+                stacks.push(next_sibling);
+            }
+            if let Some(children) = node.children {
+                stacks.push(children.first);
+            }
+
+            Some((NodeIndex::Branch(idx), &node.value))
+        })
+    }
+
+    /// Iterate mutably over the Scene Graph out of order. This is useful for speed when every value
+    /// needs a change and the order doesn't matter, like clearing a dirty flag. Like
+    /// [iter_out_of_order], this never yields the root. Use `root_mut` for that.
+    ///
+    /// [iter_out_of_order]: Self::iter_out_of_order
+    pub fn iter_out_of_order_mut(&mut self) -> impl Iterator<Item = (NodeIndex, &mut T)> {
+        self.arena.iter_mut().map(|(k, v)| (NodeIndex::Branch(k), &mut v.value))
+    }
+
+    /// Iterate immutably over the Scene Graph out of order and in parallel. Like
+    /// [iter_out_of_order], this never yields the root.
+    ///
+    /// [iter_out_of_order]: Self::iter_out_of_order
+    #[cfg(feature = "rayon")]
+    pub fn par_iter_out_of_order(&self) -> impl rayon::iter::ParallelIterator<Item = (NodeIndex, &T)>
+    where
+        T: Sync,
+    {
+        use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+        // every slot is independent, so we can split them up however we like. thunderdome can't hand
+        // out a slot past `u32::MAX`, so clamping there never skips a node.
+        let slots = u32::try_from(self.arena.capacity()).unwrap_or(u32::MAX);
+        (0..slots).into_par_iter().filter_map(|slot| {
+            let idx = self.arena.contains_slot(slot)?;
+
+            Some((NodeIndex::Branch(idx), &self.arena[idx].value))
+        })
+    }
+
+    /// Iterate immutably over the Scene Graph in a depth first traversal.
+    pub fn iter_from_node(&self, node_index: NodeIndex) -> Result<SceneGraphIter<'_, T>, NodeDoesNotExist> {
+        let (parent_value, children) = match node_index {
+            NodeIndex::Root => (&self.root, self.root_children.as_ref()),
+            NodeIndex::Branch(idx) => {
+                let node = self.arena.get(idx).ok_or(NodeDoesNotExist)?;
+
+                (&node.value, node.children.as_ref())
+            }
+        };
+
+        Ok(SceneGraphIter::new(self, node_index, parent_value, children))
+    }
+
+    /// Iterate immutably over the Scene Graph in a breadth first traversal, visiting every node at
+    /// one depth before descending to the next.
+    pub fn iter_bfs(&self) -> SceneGraphBfsIter<'_, T> {
+        self.iter_bfs_from_node(NodeIndex::Root).unwrap()
+    }
+
+    /// Iterate immutably over the Scene Graph in a breadth first traversal, starting from the given
+    /// node.
+    pub fn iter_bfs_from_node(&self, node_index: NodeIndex) -> Result<SceneGraphBfsIter<'_, T>, NodeDoesNotExist> {
+        let (parent_value, children) = match node_index {
+            NodeIndex::Root => (&self.root, self.root_children.as_ref()),
+            NodeIndex::Branch(idx) => {
+                let node = self.arena.get(idx).ok_or(NodeDoesNotExist)?;
+
+                (&node.value, node.children.as_ref())
+            }
+        };
+
+        Ok(SceneGraphBfsIter::new(self, parent_value, children))
+    }
+
+    /// Iterate immutably over the Scene Graph in a post-order depth first traversal, where a node is
+    /// only visited after all of its descendants. Useful when a parent depends on its children, like
+    /// when computing bounding boxes.
+    pub fn iter_post_order(&self) -> SceneGraphPostOrderIter<'_, T> {
+        SceneGraphPostOrderIter::new(self, &self.root, self.root_children.as_ref())
+    }
+
+    /// Returns a pretty printer which renders the graph as an indented tree, like the output of the
+    /// `tree` command. Every node is printed with its `Display` impl, one per line.
+    pub fn display_tree(&self) -> DisplayTree<'_, T>
+    where
+        T: core::fmt::Display,
+    {
+        DisplayTree::new(self)
+    }
+
+    /// Returns a wrapper which prints the graph as a nested tree of values, starting at the root.
+    /// Nodes without children don't print a `children` field.
+    pub fn debug_tree(&self) -> impl core::fmt::Debug + '_
+    where
+        T: core::fmt::Debug,
+    {
+        display::DebugTree {
+            sg: self,
+            value: &self.root,
+            children: self.root_children.as_ref(),
+        }
+    }
+
+    /// Renders the graph as a Graphviz `digraph`, with one node per `NodeIndex` labeled by its
+    /// `Display` impl and an edge from every parent to each of its children, in sibling order. The
+    /// root is always named `root`.
+    pub fn to_dot(&self) -> String
+    where
+        T: core::fmt::Display,
+    {
+        display::to_dot(self)
+    }
+
+    /// Iterate mutably over the Scene Graph in a depth first traversal, starting from the given node.
+    /// The given node itself is not yielded, and, like [iter_mut], neither are parents.
+    ///
+    /// [iter_mut]: Self::iter_mut
+    pub fn iter_mut_from_node(&mut self, node_index: NodeIndex) -> Result<SceneGraphIterMut<'_, T>, NodeDoesNotExist> {
+        match node_index {
+            NodeIndex::Root => {}
+            NodeIndex::Branch(idx) => {
+                if !self.arena.contains(idx) {
+                    return Err(NodeDoesNotExist);
+                }
+            }
+        };
+
+        Ok(SceneGraphIterMut::new(self, node_index))
+    }
+
+    /// Iterate mutably over the Scene Graph in a depth first traversal, starting from the given
+    /// node, yielding the index of each node's parent, the index of the node, and its value. The
+    /// given node itself is not yielded.
+    ///
+    /// Every value is yielded exactly once, so the values can all be held onto past the end of
+    /// iteration, alongside their handles.
+    pub fn iter_mut_from_node_with_indices(
+        &mut self,
+        node_index: NodeIndex,
+    ) -> Result<SceneGraphIndexedIterMut<'_, T>, NodeDoesNotExist> {
+        self.iter_mut_from_node(node_index).map(SceneGraphIndexedIterMut::new)
+    }
+
+    /// Iterate while detaching over the Scene Graph in a depth first traversal.
+    ///
+    /// Note: the `root` will never be detached.
+    pub fn iter_detach_from_root(&mut self) -> SceneGraphDetachIter<'_, T> {
+        SceneGraphDetachIter::new(&mut self.arena, NodeIndex::Root, self.root_children.take())
+    }
+
+    /// Iterate while detaching over the Scene Graph in a depth first traversal.
+    /// This leaves the `node_index` given in the graph, but removes all its descendents.
+    pub fn iter_detach(&mut self, node_index: NodeIndex) -> Result<SceneGraphDetachIter<'_, T>, NodeDoesNotExist> {
+        let children = match node_index {
+            NodeIndex::Root => self.root_children.take(),
+            NodeIndex::Branch(br) => match self.arena.get_mut(br) {
+                Some(v) => v.children.take(),
+                None => return Err(NodeDoesNotExist),
+            },
+        };
+
+        Ok(SceneGraphDetachIter::new(&mut self.arena, node_index, children))
+    }
+
+    /// Detaches all the descendants of `node_index` at once, collecting them in a depth first
+    /// traversal. Like [iter_detach], this leaves `node_index` in the graph.
+    ///
+    /// [iter_detach]: Self::iter_detach
+    pub fn detach_into_vec(&mut self, node_index: NodeIndex) -> Result<Vec<DetachedNode<T>>, NodeDoesNotExist> {
+        Ok(self.iter_detach(node_index)?.collect())
+    }
+
+    /// Removes all the descendants of `node_index`, returning just their values in a depth first
+    /// traversal. Use [detach_into_vec] to keep where each value came from.
+    ///
+    /// [detach_into_vec]: Self::detach_into_vec
+    pub fn take_children(&mut self, node_index: NodeIndex) -> Result<Vec<T>, NodeDoesNotExist> {
+        Ok(self.iter_detach(node_index)?.map(|detached_node| detached_node.node_value).collect())
+    }
+
+    /// Iterate directly over only the *direct* children of `parent_index`.
+    ///
+    /// For example, given a graph:
+    /// ROOT:
+    ///     A
+    ///         B
+    ///         C
+    ///             D
+    /// using [iter_direct_children] and passing in the `parent_index` for `A` will only yield `B`
+    /// and `C`, *not* `D`. For that kind of depth first traversal, using `iter_on_node`.
+    ///
+    /// [iter_direct_children]: [Self::iter_direct_children]
+    pub fn iter_direct_children(
+        &self,
+        parent_index: NodeIndex,
+    ) -> Result<SceneGraphChildIter<'_, T>, NodeDoesNotExist> {
+        if let NodeIndex::Branch(idx) = parent_index {
+            self.arena.get(idx).ok_or(NodeDoesNotExist)?;
+        }
+
+        Ok(SceneGraphChildIter::new(self, parent_index))
+    }
+
+    /// Iterate over the siblings of `node_index` in order, which are the other children of its
+    /// parent. The node itself is skipped, and the Root has no siblings, so it yields nothing.
+    pub fn iter_siblings(
+        &self,
+        node_index: NodeIndex,
+    ) -> Result<impl Iterator<Item = (NodeIndex, &T)>, NodeDoesNotExist> {
+        let parent = match node_index {
+            NodeIndex::Root => None,
+            NodeIndex::Branch(idx) => Some(self.arena.get(idx).ok_or(NodeDoesNotExist)?.parent),
+        };
+        let mut current_child = parent
+            .and_then(|parent| self.get_children(parent).expect("parent exists"))
+            .map(|v| v.first);
+
+        Ok(core::iter::from_fn(move || loop {
+            let idx = current_child?;
+            let node = &self.arena[idx];
+            current_child = node.next_sibling;
+
+            if NodeIndex::Branch(idx) != node_index {
+                return Some((NodeIndex::Branch(idx), &node.value));
+            }
+        }))
+    }
+
+    /// Iterate mutably over only the *direct* children of `parent_index`. See
+    /// [iter_direct_children] for more information.
+    ///
+    /// [iter_direct_children]: Self::iter_direct_children
+    pub fn iter_direct_children_mut(
+        &mut self,
+        parent_index: NodeIndex,
+    ) -> Result<SceneGraphChildIterMut<'_, T>, NodeDoesNotExist> {
+        if let NodeIndex::Branch(idx) = parent_index {
+            self.arena.get(idx).ok_or(NodeDoesNotExist)?;
+        }
+
+        Ok(SceneGraphChildIterMut::new(self, parent_index))
+    }
+
+    /// Compares two graphs like `PartialEq`, but ignoring the order of siblings, so each node's
+    /// children are compared as a bag of subtrees rather than a list.
+    ///
+    /// This is much slower than `==`. Each child is matched by searching its counterpart's
+    /// siblings, so it's O(n^2) over the number of siblings at every level, and worse when many
+    /// subtrees are nearly equal.
+    pub fn eq_unordered(&self, other: &SceneGraph<T>) -> bool
+    where
+        T: PartialEq,
+    {
+        self.root == other.root && self.children_eq_unordered(NodeIndex::Root, other, NodeIndex::Root)
+    }
+
+    /// Feeds the root value, then every node's value and number of children in a depth first
+    /// traversal, into `state`. This captures the shape of the graph but not its `NodeIndex`es, so
+    /// it agrees with `PartialEq`: graphs which are equal always hash the same, however they were
+    /// built.
+    ///
+    /// Like any hash, the result is only stable for a given `Hasher` and version of this crate, so
+    /// don't persist it.
+    pub fn structural_hash<H: Hasher>(&self, state: &mut H)
+    where
+        T: Hash,
+    {
+        self.root.hash(state);
+        self.num_children(NodeIndex::Root).hash(state);
+
+        for (_, idx, value) in self.iter_with_indices() {
+            value.hash(state);
+            self.num_children(idx).hash(state);
+        }
+    }
+
+    /// Returns a [Cursor] on `start`, for walking around the graph one link at a time without
+    /// juggling `NodeIndex`es.
+    ///
+    /// Note: this never fails, but a cursor started on a node which doesn't exist can't go anywhere,
+    /// and its `value` is `None`.
+    pub fn cursor(&self, start: NodeIndex) -> Cursor<'_, T> {
+        Cursor::new(self, start)
+    }
+
+    /// Returns a [CursorMut] on `start`, which can walk around the graph like a [Cursor], and also
+    /// attach, remove, and change nodes where it stands.
+    pub fn cursor_mut(&mut self, start: NodeIndex) -> CursorMut<'_, T> {
+        CursorMut::new(self, start)
+    }
+
+    /// Iterate upwards over the ancestors of `node_index`, starting with its parent.
+    ///
+    /// The root is always yielded last, as `(NodeIndex::Root, &root)`. The node itself is never
+    /// yielded, so passing in `NodeIndex::Root` yields nothing.
+    pub fn iter_ancestors(&self, node_index: NodeIndex) -> Result<SceneGraphAncestorIter<'_, T>, NodeDoesNotExist> {
+        if !self.contains(node_index) {
+            return Err(NodeDoesNotExist);
+        }
+
+        Ok(SceneGraphAncestorIter::new(self, node_index))
+    }
+
+    /// Iterates over the root and then every node in a depth first traversal, with their indices.
+    fn iter_indices_with_root(&self) -> impl Iterator<Item = (NodeIndex, &T)> {
+        core::iter::once((NodeIndex::Root, &self.root))
+            .chain(self.iter_with_indices().map(|(_, idx, value)| (idx, value)))
+    }
+
+    /// Gets the children of a given node, which are `None` if the node has no children.
+    fn get_children(&self, node_index: NodeIndex) -> Result<Option<Children>, NodeDoesNotExist> {
+        match node_index {
+            NodeIndex::Root => Ok(self.root_children),
+            NodeIndex::Branch(idx) => self.arena.get(idx).map(|v| v.children).ok_or(NodeDoesNotExist),
+        }
+    }
+
+    /// Checks that the children of `node_index` are equal to those of `other_index` in `other`, in any
+    /// order. Since equality is transitive, it's fine to match each child to the first equal one.
+    fn children_eq_unordered(&self, node_index: NodeIndex, other: &SceneGraph<T>, other_index: NodeIndex) -> bool
+    where
+        T: PartialEq,
+    {
+        let children = self.collect_children(node_index).unwrap();
+        let mut other_children = other.collect_children(other_index).unwrap();
+        if children.len() != other_children.len() {
+            return false;
+        }
+
+        for idx in children {
+            let value = &self.arena[idx].value;
+            let matching = other_children.iter().position(|other_idx| {
+                other.arena[*other_idx].value == *value
+                    && self.children_eq_unordered(NodeIndex::Branch(idx), other, NodeIndex::Branch(*other_idx))
+            });
+
+            match matching {
+                Some(position) => {
+                    other_children.swap_remove(position);
+                }
+                None => return false,
+            }
+        }
+
+        true
+    }
+
+    /// Gets the children of a given node mutably.
+    fn get_children_mut(&mut self, node_index: NodeIndex) -> Result<&mut Option<Children>, NodeDoesNotExist> {
+        match node_index {
+            NodeIndex::Root => Ok(&mut self.root_children),
+            NodeIndex::Branch(idx) => self
+                .arena
+                .get_mut(idx)
+                .map(|v| &mut v.children)
+                .ok_or(NodeDoesNotExist),
+        }
+    }
+
+    /// Collects the indices of the *direct* children of a node, in order.
+    fn collect_children(&self, parent: NodeIndex) -> Result<Vec<Index>, NodeDoesNotExist> {
+        let children = self.get_children(parent)?;
+        let mut out = Vec::with_capacity(children.map(|v| v.count).unwrap_or_default());
+
+        let mut current_child = children.map(|v| v.first);
+        while let Some(idx) = current_child {
+            out.push(idx);
+            current_child = self.arena[idx].next_sibling;
+        }
+
+        Ok(out)
+    }
+
+    /// Relinks the sibling chain of a parent so its children are in the given order. `order` must
+    /// contain exactly the current children of `parent`.
+    fn relink_children(&mut self, parent: NodeIndex, order: &[Index]) {
+        let (Some(first), Some(last)) = (order.first(), order.last()) else { return };
+
+        let children = self
+            .get_children_mut(parent)
+            .expect("parent exists")
+            .as_mut()
+            .expect("parent has children");
+        children.first = *first;
+        children.last = *last;
+
+        for (i, idx) in order.iter().enumerate() {
+            let node = &mut self.arena[*idx];
+            node.last_sibling = i.checked_sub(1).map(|i| order[i]);
+            node.next_sibling = order.get(i + 1).copied();
+        }
+    }
+
+    /// Checks that a node can be moved next to `sibling`, returning both of their indices. The root
+    /// has no siblings, so it can't be either of them.
+    fn check_positional_move(
+        &self,
+        moving_node_idx: NodeIndex,
+        sibling: NodeIndex,
+    ) -> Result<(Index, Index), MoveError> {
+        let (NodeIndex::Branch(moving_node_idx), NodeIndex::Branch(sibling_idx)) = (moving_node_idx, sibling) else {
+            return Err(MoveError::NodeDoesNotExist);
+        };
+        if !self.arena.contains(moving_node_idx) || !self.arena.contains(sibling_idx) {
+            return Err(MoveError::NodeDoesNotExist);
+        }
+
+        // our new parent would be in our own subtree
+        if self.is_ancestor_of(NodeIndex::Branch(moving_node_idx), sibling) {
+            return Err(MoveError::WouldCreateCycle);
+        }
+
+        Ok((moving_node_idx, sibling_idx))
+    }
+
+    /// Checks the links right around one node: that its parent exists, that its siblings link back
+    /// to it, and that the ends of its own children really are the ends. This is the cheap, local
+    /// version of [validate], so debug builds can run it after every change without making
+    /// building a graph quadratic.
+    ///
+    /// [validate]: Self::validate
+    fn check_links(&self, node_index: NodeIndex) -> Result<(), ValidationError> {
+        if let NodeIndex::Branch(idx) = node_index {
+            let node = self.arena.get(idx).ok_or(ValidationError::Unreachable(node_index))?;
+            let parent_children = self
+                .get_children(node.parent)
+                .map_err(|_| ValidationError::DanglingParent(node_index))?
+                .ok_or(ValidationError::WrongParent(node_index))?;
+
+            match node.last_sibling {
+                Some(last_sibling) => {
+                    let sibling = self
+                        .arena
+                        .get(last_sibling)
+                        .ok_or(ValidationError::WrongPreviousSibling(node_index))?;
+                    if sibling.next_sibling != Some(idx) {
+                        return Err(ValidationError::WrongPreviousSibling(node_index));
+                    }
+                    if sibling.parent != node.parent {
+                        return Err(ValidationError::WrongParent(NodeIndex::Branch(last_sibling)));
+                    }
+                }
+                None if parent_children.first != idx => return Err(ValidationError::WrongPreviousSibling(node_index)),
+                None => {}
+            }
+
+            match node.next_sibling {
+                Some(next_sibling) => {
+                    let sibling = self
+                        .arena
+                        .get(next_sibling)
+                        .ok_or(ValidationError::DanglingChild(node.parent))?;
+                    if sibling.last_sibling != Some(idx) {
+                        return Err(ValidationError::WrongPreviousSibling(NodeIndex::Branch(next_sibling)));
+                    }
+                    if sibling.parent != node.parent {
+                        return Err(ValidationError::WrongParent(NodeIndex::Branch(next_sibling)));
+                    }
+                }
+                None if parent_children.last != idx => return Err(ValidationError::WrongLastChild(node.parent)),
+                None => {}
+            }
+        }
+
+        let Some(children) = self.get_children(node_index).map_err(|_| ValidationError::Unreachable(node_index))?
+        else {
+            return Ok(());
+        };
+        let first = self.arena.get(children.first).ok_or(ValidationError::DanglingChild(node_index))?;
+        let last = self.arena.get(children.last).ok_or(ValidationError::DanglingChild(node_index))?;
+
+        if first.parent != node_index {
+            return Err(ValidationError::WrongParent(NodeIndex::Branch(children.first)));
+        }
+        if first.last_sibling.is_some() {
+            return Err(ValidationError::WrongPreviousSibling(NodeIndex::Branch(children.first)));
+        }
+        if last.parent != node_index {
+            return Err(ValidationError::WrongParent(NodeIndex::Branch(children.last)));
+        }
+        if last.next_sibling.is_some() {
+            return Err(ValidationError::WrongLastChild(node_index));
+        }
+        if children.count == 0 {
+            return Err(ValidationError::WrongChildCount(node_index));
+        }
+
+        Ok(())
+    }
+
+    /// Checks that walking up the parents of a node reaches the Root, which is O(n) over its depth.
+    /// A cycle would make that walk go on forever, so this gives up once it has taken more steps
+    /// than there are nodes.
+    fn check_acyclic(&self, node_index: NodeIndex) -> Result<(), ValidationError> {
+        let mut current = node_index;
+
+        for _ in 0..=self.arena.len() {
+            let NodeIndex::Branch(idx) = current else { return Ok(()) };
+            let parent = self.arena.get(idx).ok_or(ValidationError::Unreachable(current))?.parent;
+            if !self.contains(parent) {
+                return Err(ValidationError::DanglingParent(current));
+            }
+
+            current = parent;
+        }
+
+        Err(ValidationError::VisitedTwice(node_index))
+    }
+
+    /// Unlinks a node from its parent and its siblings, leaving it (and its children) in the arena
+    /// so it can be placed somewhere else.
+    fn unlink_node(&mut self, idx: Index) {
+        let node = &mut self.arena[idx];
+        let parent = node.parent;
+        let next_sibling = node.next_sibling.take();
+        let last_sibling = node.last_sibling.take();
+
+        self.fix_parent(next_sibling, last_sibling, parent, idx);
+    }
+
+    /// Places a node as part of moving or attaching it.
+    fn place_node(&mut self, new_parent: NodeIndex, node_to_place: Index) -> Result<(), ParentNodeNotFound> {
+        // okay, now we gotta ATTACH ourselves back, without being monsters about it
+        let parent_children = match new_parent {
+            NodeIndex::Root => &mut self.root_children,
+            NodeIndex::Branch(idx) => &mut self.arena.get_mut(idx).ok_or(ParentNodeNotFound)?.children,
+        };
+
+        // slap ourselves in here
+        match parent_children.as_mut() {
+            Some(children) => {
+                let old_last = children.last;
+                children.last = node_to_place;
+                children.count += 1;
+
+                let last_sibling = &mut self.arena[old_last];
+                last_sibling.next_sibling = Some(node_to_place);
+
+                // fix this up too
+                self.arena[node_to_place].last_sibling = Some(old_last);
+            }
+            None => {
+                // this is the easy case
+                *parent_children = Some(Children {
+                    first: node_to_place,
+                    last: node_to_place,
+                    count: 1,
+                });
+            }
+        };
+
+        Ok(())
+    }
+
+    /// Places a node as the first child of a parent as part of moving or attaching it.
+    fn place_node_front(&mut self, new_parent: NodeIndex, node_to_place: Index) -> Result<(), ParentNodeNotFound> {
+        let parent_children = match new_parent {
+            NodeIndex::Root => &mut self.root_children,
+            NodeIndex::Branch(idx) => &mut self.arena.get_mut(idx).ok_or(ParentNodeNotFound)?.children,
+        };
+
+        match parent_children.as_mut() {
+            Some(children) => {
+                let old_first = children.first;
+                children.first = node_to_place;
+                children.count += 1;
+
+                let next_sibling = &mut self.arena[old_first];
+                next_sibling.last_sibling = Some(node_to_place);
+
+                self.arena[node_to_place].next_sibling = Some(old_first);
+            }
+            None => {
+                *parent_children = Some(Children {
+                    first: node_to_place,
+                    last: node_to_place,
+                    count: 1,
+                });
+            }
+        };
+
+        Ok(())
+    }
+
+    /// Places a node under `parent`, directly after `last_sibling`, or at the front if there isn't
+    /// one.
+    fn place_node_at(&mut self, parent: NodeIndex, last_sibling: Option<Index>, node_to_place: Index) {
+        match last_sibling {
+            Some(last_sibling) => self.place_node_after(last_sibling, node_to_place),
+            None => {
+                self.arena[node_to_place].parent = parent;
+                self.place_node_front(parent, node_to_place).expect("parent exists");
+            }
+        }
+    }
+
+    /// Places a node directly after a sibling as part of moving or attaching it.
+    fn place_node_after(&mut self, sibling: Index, node_to_place: Index) {
+        let sibling_node = &mut self.arena[sibling];
+        let parent = sibling_node.parent;
+        let old_next = sibling_node.next_sibling;
+        sibling_node.next_sibling = Some(node_to_place);
+
+        let placed_node = &mut self.arena[node_to_place];
+        placed_node.parent = parent;
+        placed_node.last_sibling = Some(sibling);
+        placed_node.next_sibling = old_next;
+
+        let parent_children = self
+            .get_children_mut(parent)
+            .expect("sibling has a parent")
+            .as_mut()
+            .expect("parent has a child");
+        parent_children.count += 1;
+
+        match old_next {
+            Some(old_next) => self.arena[old_next].last_sibling = Some(node_to_place),
+            None => parent_children.last = node_to_place,
+        }
+    }
+
+    /// Places a node directly before a sibling as part of moving or attaching it.
+    fn place_node_before(&mut self, sibling: Index, node_to_place: Index) {
+        let sibling_node = &mut self.arena[sibling];
+        let parent = sibling_node.parent;
+        let old_last = sibling_node.last_sibling;
+        sibling_node.last_sibling = Some(node_to_place);
+
+        let placed_node = &mut self.arena[node_to_place];
+        placed_node.parent = parent;
+        placed_node.last_sibling = old_last;
+        placed_node.next_sibling = Some(sibling);
+
+        let parent_children = self
+            .get_children_mut(parent)
+            .expect("sibling has a parent")
+            .as_mut()
+            .expect("parent has a child");
+        parent_children.count += 1;
+
+        match old_last {
+            Some(old_last) => self.arena[old_last].next_sibling = Some(node_to_place),
+            None => parent_children.first = node_to_place,
+        }
+    }
+
+    /// Fixes a parent with a removed child.
+    ///
+    /// This can only fail if the links in the graph are already broken, in which case it panics
+    /// with the invariant which was violated. Debug builds also check that the removed child's
+    /// sibling links agree with its parent.
+    fn fix_parent(
+        &mut self,
+        removed_next_sibling: Option<Index>,
+        removed_last_sibling: Option<Index>,
+        removed_parent: NodeIndex,
+        removed_idx: Index,
+    ) {
+        // fix up the parent if it was the first child...
+
+        let parent_children = match removed_parent {
+            NodeIndex::Root => self.root_children,
+            NodeIndex::Branch(idx) => self
+                .arena
+                .get(idx)
+                .unwrap_or_else(|| panic!("parent {:?} of {:?} does not exist", removed_parent, removed_idx))
+                .children,
+        };
+        let mut parent_children = parent_children
+            .unwrap_or_else(|| panic!("parent {:?} of {:?} has no children", removed_parent, removed_idx));
+
+        // if these fail, the sibling links of the removed node are stale
+        debug_assert!(
+            (removed_last_sibling.is_none()) == (parent_children.first == removed_idx),
+            "{:?} has a previous sibling if and only if it isn't the first child of {:?}",
+            removed_idx,
+            removed_parent
+        );
+        debug_assert!(
+            (removed_next_sibling.is_none()) == (parent_children.last == removed_idx),
+            "{:?} has a next sibling if and only if it isn't the last child of {:?}",
+            removed_idx,
+            removed_parent
+        );
+
+        if parent_children.first == parent_children.last && parent_children.first == removed_idx {
+            match removed_parent {
+                NodeIndex::Root => self.root_children = None,
+                NodeIndex::Branch(idx) => self.arena[idx].children = None,
+            };
+        } else {
+            // extremely hard to follow the logic of this unwrap here, but if this branch is taken,
+            // then we're *never* the last child, which means we have a sibling.
+            if parent_children.first == removed_idx {
+                parent_children.first = removed_next_sibling.expect("the first of many children has a next sibling");
+            }
+
+            if parent_children.last == removed_idx {
+                parent_children.last = removed_last_sibling.expect("the last of many children has a last sibling");
+            }
+
+            debug_assert!(
+                parent_children.count > 1,
+                "{:?} has more than one child, but a count of {}",
+                removed_parent,
+                parent_children.count
+            );
+            parent_children.count -= 1;
+
+            if let Some(last_sibling) = removed_last_sibling {
+                let last_sibling = self
+                    .arena
+                    .get_mut(last_sibling)
+                    .unwrap_or_else(|| panic!("last sibling {:?} of {:?} does not exist", last_sibling, removed_idx));
+                last_sibling.next_sibling = removed_next_sibling;
+            }
+
+            if let Some(next_sibling) = removed_next_sibling {
+                let next_sibling = self
+                    .arena
+                    .get_mut(next_sibling)
+                    .unwrap_or_else(|| panic!("next sibling {:?} of {:?} does not exist", next_sibling, removed_idx));
+                next_sibling.last_sibling = removed_last_sibling;
+            }
+
+            // finally, dump our updated parent children back
+            match removed_parent {
+                NodeIndex::Root => self.root_children = Some(parent_children),
+                NodeIndex::Branch(idx) => self.arena[idx].children = Some(parent_children),
+            };
+        }
+    }
+}
+
+impl<T: Default> Default for SceneGraph<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> IntoIterator for SceneGraph<T> {
+    type Item = T;
+
+    type IntoIter = SceneGraphIntoIter<T>;
+
+    /// Consumes the graph, yielding every value in a depth first traversal. Unlike `iter`, the root
+    /// is included, and is always yielded first.
+    fn into_iter(self) -> Self::IntoIter {
+        SceneGraphIntoIter::new(self)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SceneGraph<T> {
+    type Item = (&'a T, &'a T);
+
+    type IntoIter = SceneGraphIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut SceneGraph<T> {
+    type Item = &'a mut T;
+
+    type IntoIter = SceneGraphIterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// Indexes into the SceneGraph, returning the value of the given node.
+///
+/// # Panics
+///
+/// This panics if `node_index` is `NodeIndex::Root`, which is not a true node (use `root` instead),
+/// or if the node doesn't exist. Use `get` for a non-panicking alternative.
+impl<T> core::ops::Index<NodeIndex> for SceneGraph<T> {
+    type Output = T;
+
+    fn index(&self, node_index: NodeIndex) -> &Self::Output {
+        match node_index {
+            NodeIndex::Root => panic!("cannot index the root of a SceneGraph, use `root` instead"),
+            NodeIndex::Branch(idx) => match self.arena.get(idx) {
+                Some(node) => &node.value,
+                None => panic!("node {:?} does not exist in the SceneGraph", node_index),
+            },
+        }
+    }
+}
+
+/// Mutably indexes into the SceneGraph, returning the value of the given node.
+///
+/// # Panics
+///
+/// This panics if `node_index` is `NodeIndex::Root`, which is not a true node (use `root_mut`
+/// instead), or if the node doesn't exist. Use `get_mut` for a non-panicking alternative.
+impl<T> core::ops::IndexMut<NodeIndex> for SceneGraph<T> {
+    fn index_mut(&mut self, node_index: NodeIndex) -> &mut Self::Output {
+        match node_index {
+            NodeIndex::Root => panic!("cannot index the root of a SceneGraph, use `root_mut` instead"),
+            NodeIndex::Branch(idx) => match self.arena.get_mut(idx) {
+                Some(node) => &mut node.value,
+                None => panic!("node {:?} does not exist in the SceneGraph", node_index),
+            },
+        }
+    }
+}
+
+/// Two SceneGraphs are equal when their roots are equal and they have the same shape, with equal
+/// values in the same sibling order. The underlying `NodeIndex`es are not compared, so graphs built
+/// in different ways can still be equal.
+impl<T: PartialEq> PartialEq for SceneGraph<T> {
+    fn eq(&self, other: &Self) -> bool {
+        if self.root != other.root {
+            return false;
+        }
+
+        // we walk both sibling chains in lockstep
+        let mut stacks = vec![(
+            self.root_children.map(|v| v.first),
+            other.root_children.map(|v| v.first),
+        )];
+
+        while let Some(stack_frame) = stacks.pop() {
+            match stack_frame {
+                (None, None) => {}
+                (Some(lhs), Some(rhs)) => {
+                    let lhs = &self.arena[lhs];
+                    let rhs = &other.arena[rhs];
+
+                    if lhs.value != rhs.value {
+                        return false;
+                    }
+
+                    stacks.push((lhs.next_sibling, rhs.next_sibling));
+                    stacks.push((lhs.children.map(|v| v.first), rhs.children.map(|v| v.first)));
+                }
+                _ => return false,
+            }
+        }
+
+        true
+    }
+}
+
+impl<T: Eq> Eq for SceneGraph<T> {}
+
+/// Hashes the same way as [SceneGraph::structural_hash], so graphs which are equal hash the same.
+impl<T: Hash> Hash for SceneGraph<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.structural_hash(state);
+    }
+}
+
+/// A wrapper around the values given to the SceneGraph. This struct includes the data on the
+/// relationships to other nodes, in addition to the value placed at the node.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub struct Node<T> {
+    /// The value contained within the node.
+    pub value: T,
+    parent: NodeIndex,
+    children: Option<Children>,
+    last_sibling: Option<Index>,
+    next_sibling: Option<Index>,
+}
+
+impl<T> Node<T> {
+    fn new(value: T, parent: NodeIndex) -> Self {
+        Self {
+            value,
+            parent,
+            last_sibling: None,
+            next_sibling: None,
+            children: None,
+        }
+    }
+
+    /// Returns true if this node has children.
+    pub fn has_children(&self) -> bool {
+        self.children.is_some()
+    }
+
+    /// Iterate directly over only the *direct* children of `parent_index`.
+    ///
+    /// For example, given a graph:
+    /// ROOT:
+    ///     A
+    ///         B
+    ///         C
+    ///             D
+    /// using `iter_direct_children` and passing in the `parent_index` for `A` will only yield `B`
+    /// and `C`, *not* `D`. For that kind of depth first traversal, using `iter_on_node`.
+    ///
+    /// Note: passing in a SceneGraph of a different kind than this node belongs to (but of the same
+    /// type) will create logic errors or panics.
+    pub fn iter_direct_children<'a>(&'a self, sg: &'a SceneGraph<T>) -> SceneGraphChildIter<'a, T> {
+        SceneGraphChildIter::with_children(sg, self.children.as_ref())
+    }
+
+    /// Returns the index of the parent.
+    pub fn parent(&self) -> NodeIndex {
+        self.parent
+    }
+
+    /// Returns the index of the next sibling, if this node is not the last child of its parent.
+    pub fn next_sibling(&self) -> Option<NodeIndex> {
+        self.next_sibling.map(NodeIndex::Branch)
+    }
+
+    /// Returns the index of the previous sibling, if this node is not the first child of its
+    /// parent.
+    pub fn previous_sibling(&self) -> Option<NodeIndex> {
+        self.last_sibling.map(NodeIndex::Branch)
+    }
+
+    /// The `Debug` impl of `Node` skips its `value`, since `T` might not be `Debug`. This returns a
+    /// wrapper which prints the `value` too.
+    pub fn debug_with_value(&self) -> impl core::fmt::Debug + '_
+    where
+        T: core::fmt::Debug,
+    {
+        display::DebugNodeWithValue(self)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+struct Children {
+    first: Index,
+    last: Index,
+    count: usize,
+}
+
+/// A node waiting to be moved by `split_off`, along with the links it'll have in the new graph.
+#[derive(Debug, Clone, Copy)]
+struct SplitFrame {
+    idx: Index,
+    new_parent: NodeIndex,
+    last_sibling: Option<Index>,
+    /// The new index of the first of these siblings, once it's been moved.
+    first: Option<Index>,
+    count: usize,
+}
+
+impl SplitFrame {
+    fn first_child(children: Children, new_parent: NodeIndex) -> Self {
+        Self {
+            idx: children.first,
+            new_parent,
+            last_sibling: None,
+            first: None,
+            count: children.count,
+        }
+    }
+}
+
+/// Holds a parent's `Children` while they're being built up, and writes them back to the parent
+/// when dropped, even if that's because of a panic partway through.
+struct ChildrenGuard<'a, T> {
+    sg: &'a mut SceneGraph<T>,
+    parent: NodeIndex,
+    children: Option<Children>,
+}
+
+impl<T> Drop for ChildrenGuard<'_, T> {
+    fn drop(&mut self) {
+        *self.sg.get_children_mut(self.parent).unwrap() = self.children;
+    }
+}
+
+impl<T> core::fmt::Debug for Node<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Node")
+            .field("parent", &self.parent)
+            .field("children", &self.children)
+            .field("next_sibling", &self.next_sibling)
+            .finish()
+    }
+}
+
+/// A node index into the SceneGraph.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum NodeIndex {
+    /// Signifies that the index corresponds to the root of the graph.
+    Root,
+
+    /// Signifies a non-root node.
+    Branch(thunderdome::Index),
+}
+
+impl NodeIndex {
+    /// Returns `true` if the node index is [`Root`].
+    ///
+    /// [`Root`]: NodeIndex::Root
+    #[must_use]
+    pub fn is_root(&self) -> bool {
+        matches!(self, Self::Root)
+    }
+
+    /// Converts the index to a `u64`, which is useful for storing handles outside of Rust. The
+    /// `Root` is always `0`, which is never a valid `Branch`.
+    ///
+    /// Note: these bits are only meaningful for the graph which the index came from, and they
+    /// don't survive a serde round trip of the graph, which doesn't preserve `NodeIndex`es.
+    #[must_use]
+    pub fn to_bits(&self) -> u64 {
+        match self {
+            Self::Root => 0,
+            Self::Branch(idx) => idx.to_bits(),
+        }
+    }
+
+    /// Creates an index from bits made with [to_bits]. Returns `None` if the bits could never have
+    /// come from [to_bits].
+    ///
+    /// [to_bits]: Self::to_bits
+    #[must_use]
+    pub fn from_bits(bits: u64) -> Option<Self> {
+        if bits == 0 {
+            return Some(Self::Root);
+        }
+
+        Index::from_bits(bits).map(Self::Branch)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+#[error("parent node not found")]
+/// The parent node requested was not found.
+pub struct ParentNodeNotFound;
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+#[error("node does not exist")]
+/// The node does not exist.
+pub struct NodeDoesNotExist;
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+#[error("cannot remove the root")]
+/// The root cannot be removed from the graph.
+pub struct CannotRemoveRoot;
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+/// An error which can occur when moving a node with [SceneGraph::move_node].
+pub enum MoveError {
+    /// The node being moved, or its new parent, does not exist.
+    #[error("node does not exist")]
+    NodeDoesNotExist,
+
+    /// The new parent is the node being moved or one of its descendants.
+    #[error("moving the node would create a cycle")]
+    WouldCreateCycle,
+}
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+/// A problem with the internal links of a graph, found by [SceneGraph::validate].
+pub enum ValidationError {
+    /// The node's parent does not exist.
+    #[error("the parent of {0:?} does not exist")]
+    DanglingParent(NodeIndex),
+
+    /// One of the node's children does not exist.
+    #[error("a child of {0:?} does not exist")]
+    DanglingChild(NodeIndex),
+
+    /// The node was reached more than once while walking down from the root, so there is a cycle,
+    /// or a node is linked into two places.
+    #[error("{0:?} was reached more than once")]
+    VisitedTwice(NodeIndex),
+
+    /// The node's parent doesn't match the node whose children it's linked into.
+    #[error("{0:?} has the wrong parent")]
+    WrongParent(NodeIndex),
+
+    /// The node's previous sibling doesn't match the sibling linking to it.
+    #[error("{0:?} has the wrong previous sibling")]
+    WrongPreviousSibling(NodeIndex),
+
+    /// The node's last child isn't the end of its chain of children.
+    #[error("{0:?} has the wrong last child")]
+    WrongLastChild(NodeIndex),
+
+    /// The node's child count doesn't match the length of its chain of children.
+    #[error("{0:?} has the wrong child count")]
+    WrongChildCount(NodeIndex),
+
+    /// The node can't be reached by walking down from the root.
+    #[error("{0:?} is not reachable from the root")]
+    Unreachable(NodeIndex),
+}
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+/// An error which can occur when moving a node between graphs with [SceneGraph::detach_into].
+pub enum TransferError {
+    /// The node being moved does not exist, or is the root, which can't be moved.
+    #[error("node does not exist")]
+    NodeDoesNotExist,
+
+    /// The new parent does not exist in the destination graph.
+    #[error("parent node not found")]
+    ParentNodeNotFound,
+}
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+/// An error which can occur when swapping two nodes with [SceneGraph::swap_nodes].
+pub enum SwapError {
+    /// One of the nodes does not exist, or is the root, which can't be swapped.
+    #[error("node does not exist")]
+    NodeDoesNotExist,
+
+    /// One of the nodes is an ancestor of the other.
+    #[error("cannot swap a node with its own ancestor")]
+    AncestorOfOther,
+}
+
+/// Error type for operations on two siblings.
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum SiblingError {
+    /// One of the nodes does not exist, or is the root, which has no siblings.
+    #[error("node does not exist")]
+    NodeDoesNotExist,
+
+    /// The nodes do not share a parent.
+    #[error("nodes are not siblings")]
+    NotSiblings,
+}
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+/// An error which can occur when removing a node with [CursorMut::remove_current].
+pub enum RemoveError {
+    /// The node was already removed.
+    #[error("node does not exist")]
+    NodeDoesNotExist,
+
+    /// The root cannot be removed from the graph.
+    #[error("cannot remove the root")]
+    CannotRemoveRoot,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_values(sg: &SceneGraph<&'static str>) -> Vec<&'static str> {
+        let mut out = vec![];
+        for (_, v) in sg.iter() {
+            out.push(*v);
+        }
+
+        out
+    }
+
+    #[test]
+    fn basic_attach() {
+        let mut sg = SceneGraph::new("Root");
+        let root_idx = NodeIndex::Root;
+        sg.attach(root_idx, "First Child").unwrap();
+        let second_child = sg.attach(root_idx, "Second Child").unwrap();
+        sg.attach(second_child, "First Grandchild").unwrap();
+
+        assert_eq!(get_values(&sg), vec!["First Child", "Second Child", "First Grandchild"]);
+    }
+
+    #[test]
+    fn attach_internals() {
+        let mut sg = SceneGraph::new("Root");
+
+        assert_eq!(sg.root_children, None);
+
+        let root_idx = NodeIndex::Root;
+
+        let first_idx = sg.attach(root_idx, "First Child").unwrap();
+
+        // assert_eq!(sg.get_root().num_children, 1);
+        assert_eq!(NodeIndex::Branch(sg.root_children.unwrap().first), first_idx);
+        assert_eq!(NodeIndex::Branch(sg.root_children.unwrap().last), first_idx);
+
+        let second_idx = sg.attach(root_idx, "Second Child").unwrap();
+
+        assert_eq!(NodeIndex::Branch(sg.root_children.unwrap().first), first_idx);
+        assert_eq!(NodeIndex::Branch(sg.root_children.unwrap().last), second_idx);
+
+        assert_eq!(
+            sg.get(first_idx).unwrap().next_sibling.map(NodeIndex::Branch),
+            Some(second_idx)
+        );
+        assert_eq!(sg.get(first_idx).unwrap().last_sibling, None);
+
+        assert_eq!(sg.get(second_idx).unwrap().next_sibling, None);
+        assert_eq!(
+            sg.get(second_idx).unwrap().last_sibling.map(NodeIndex::Branch),
+            Some(first_idx)
+        );
+    }
+
+    #[test]
+    fn detach_basic() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let second_child = sg.attach_at_root("Second Child");
+        let third_child = sg.attach_at_root("Third Child");
+
+        let second_child = sg.detach(second_child).unwrap();
+        assert_eq!(*second_child.root(), "Second Child");
+
+        assert_eq!(NodeIndex::Branch(sg.root_children.unwrap().first), first_child);
+        assert_eq!(NodeIndex::Branch(sg.root_children.unwrap().last), third_child);
+
+        assert_eq!(sg.get(first_child).unwrap().last_sibling, None);
+        assert_eq!(
+            sg.get(first_child).unwrap().next_sibling.map(NodeIndex::Branch),
+            Some(third_child)
+        );
+
+        assert_eq!(
+            sg.get(third_child).unwrap().last_sibling.map(NodeIndex::Branch),
+            Some(first_child)
+        );
+        assert_eq!(sg.get(third_child).unwrap().next_sibling, None);
+
+        assert_eq!(get_values(&sg), vec!["First Child", "Third Child"]);
+
+        let g = sg.attach(third_child, "First Grandchild").unwrap();
+        sg.attach(g, "Second Grandchild").unwrap();
+        let g_3 = sg.attach(g, "Third Grandchild").unwrap();
+        sg.attach(g_3, "First Greatgrandchild").unwrap();
+
+        let third_child_tree = sg.detach(third_child).unwrap();
+        assert_eq!(get_values(&sg), vec!["First Child"]);
+        assert_eq!(
+            get_values(&third_child_tree),
+            vec![
+                "First Grandchild",
+                "Second Grandchild",
+                "Third Grandchild",
+                "First Greatgrandchild"
+            ]
+        );
+        assert_eq!(*third_child_tree.root(), "Third Child");
+    }
+
+    #[test]
+    fn move_node() {
+        let mut sg = SceneGraph::new("Root");
+        let fg = sg.attach(NodeIndex::Root, "First Child").unwrap();
+        sg.attach(fg, "First Grandchild").unwrap();
+        sg.attach(fg, "Second Grandchild").unwrap();
+        sg.attach(fg, "Third Grandchild").unwrap();
+        let second_child = sg.attach(NodeIndex::Root, "Second Child").unwrap();
+
+        assert_eq!(
+            Vec::from_iter(sg.iter_direct_children(fg).unwrap().cloned()),
+            vec!["First Grandchild", "Second Grandchild", "Third Grandchild",]
+        );
+
+        sg.move_node(fg, second_child).unwrap();
+
+        assert_eq!(
+            Vec::from_iter(sg.iter_direct_children(NodeIndex::Root).unwrap().cloned()),
+            vec!["Second Child",]
+        );
+
+        assert_eq!(
+            Vec::from_iter(sg.iter_direct_children(fg).unwrap().cloned()),
+            vec!["First Grandchild", "Second Grandchild", "Third Grandchild",]
+        );
+
+        assert_eq!(
+            Vec::from_iter(sg.iter_direct_children(second_child).unwrap().cloned()),
+            vec!["First Child",]
+        );
+    }
+
+    #[test]
+    fn sibling_accessors() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let second_child = sg.attach_at_root("Second Child");
+        let third_child = sg.attach_at_root("Third Child");
+
+        let second = sg.get(second_child).unwrap();
+        assert_eq!(second.previous_sibling(), Some(first_child));
+        assert_eq!(second.next_sibling(), Some(third_child));
+
+        assert_eq!(sg.get(first_child).unwrap().previous_sibling(), None);
+        assert_eq!(sg.get(third_child).unwrap().next_sibling(), None);
+    }
+
+    #[test]
+    fn depth() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        let great_grandchild = sg.attach(grandchild, "First Great-Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+
+        assert_eq!(sg.depth(NodeIndex::Root), Some(0));
+        assert_eq!(sg.depth(first_child), Some(1));
+        assert_eq!(sg.depth(grandchild), Some(2));
+        assert_eq!(sg.depth(great_grandchild), Some(3));
+
+        sg.move_node(grandchild, NodeIndex::Root).unwrap();
+        assert_eq!(sg.depth(grandchild), Some(1));
+        assert_eq!(sg.depth(great_grandchild), Some(2));
+
+        let detached = sg.detach(grandchild).unwrap();
+        assert_eq!(sg.depth(grandchild), None);
+        let (detached_great_grandchild, _) = detached.iter_out_of_order().next().unwrap();
+
+        let (new_grandchild, helper_map) = sg.attach_graph(second_child, detached).unwrap();
+        assert_eq!(sg.depth(new_grandchild), Some(2));
+        assert_eq!(sg.depth(helper_map[&detached_great_grandchild]), Some(3));
+    }
+
+    #[test]
+    fn count_at_depth() {
+        let mut sg = SceneGraph::new("Root");
+        assert_eq!(sg.count_at_depth(0), 1);
+        assert_eq!(sg.count_at_depth(1), 0);
+
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach(grandchild, "First Great-Grandchild").unwrap();
+        sg.attach(first_child, "Second Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+        sg.attach(second_child, "Third Grandchild").unwrap();
+        sg.attach_at_root("Third Child");
+
+        assert_eq!(
+            Vec::from_iter((0..=4).map(|depth| sg.count_at_depth(depth))),
+            vec![1, 3, 3, 1, 0]
+        );
+        assert_eq!((1..=sg.height()).map(|depth| sg.count_at_depth(depth)).sum::<usize>(), sg.len());
+    }
+
+    #[test]
+    fn depth_histogram() {
+        let mut sg = SceneGraph::new("Root");
+        assert_eq!(sg.depth_histogram(), vec![1]);
+
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach(grandchild, "First Great-Grandchild").unwrap();
+        sg.attach(first_child, "Second Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+        sg.attach(second_child, "Third Grandchild").unwrap();
+        sg.attach_at_root("Third Child");
+
+        let histogram = sg.depth_histogram();
+        assert_eq!(histogram, vec![1, 3, 3, 1]);
+        assert_eq!(histogram.len(), sg.height() + 1);
+        assert_eq!(histogram.iter().sum::<usize>(), sg.len() + 1);
+        for (depth, count) in histogram.iter().enumerate() {
+            assert_eq!(sg.count_at_depth(depth), *count);
+        }
+    }
+
+    #[test]
+    fn height() {
+        let mut sg = SceneGraph::new("Root");
+        assert_eq!(sg.height(), 0);
+
+        let first_child = sg.attach_at_root("First Child");
+        let second_child = sg.attach_at_root("Second Child");
+        assert_eq!(sg.height(), 1);
+
+        sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach(second_child, "Second Grandchild").unwrap();
+        assert_eq!(sg.height(), 2);
+
+        // the deepest branch wins, even if it's not the first or the last
+        let third_child = sg.attach_at_root("Third Child");
+        let third_grandchild = sg.attach(third_child, "Third Grandchild").unwrap();
+        let great_grandchild = sg.attach(third_grandchild, "Great-Grandchild").unwrap();
+        sg.attach(great_grandchild, "Great-Great-Grandchild").unwrap();
+        sg.attach_at_root("Fourth Child");
+        assert_eq!(sg.height(), 4);
+
+        sg.remove(third_child).unwrap();
+        assert_eq!(sg.height(), 2);
+    }
+
+    #[test]
+    fn is_direct_child_of_root() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        let great_grandchild = sg.attach(grandchild, "First Great-Grandchild").unwrap();
+
+        assert!(sg.is_direct_child_of_root(first_child));
+        assert!(!sg.is_direct_child_of_root(great_grandchild));
+        assert!(!sg.is_direct_child_of_root(NodeIndex::Root));
+
+        assert_eq!(sg.ancestor_count(NodeIndex::Root), Some(0));
+        assert_eq!(sg.ancestor_count(first_child), Some(1));
+        assert_eq!(sg.ancestor_count(great_grandchild), Some(3));
+
+        sg.remove(first_child).unwrap();
+        assert!(!sg.is_direct_child_of_root(first_child));
+        assert_eq!(sg.ancestor_count(first_child), None);
+    }
+
+    #[test]
+    fn take_children() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach(grandchild, "First Great-Grandchild").unwrap();
+        sg.attach(first_child, "Second Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+
+        let expected = Vec::from_iter(sg.iter_from_node(first_child).unwrap().map(|(_, value)| *value));
+        let taken = sg.take_children(first_child).unwrap();
+        assert_eq!(taken, expected);
+        assert_eq!(taken, vec!["First Grandchild", "First Great-Grandchild", "Second Grandchild"]);
+
+        assert!(!sg.get(first_child).unwrap().has_children());
+        assert_eq!(get_values(&sg), vec!["First Child", "Second Child"]);
+        assert_eq!(sg.len(), 2);
+        assert!(sg.take_children(second_child).unwrap().is_empty());
+
+        assert_eq!(sg.take_children(NodeIndex::Root).unwrap(), vec!["First Child", "Second Child"]);
+        assert!(sg.is_empty());
+        assert_eq!(sg.take_children(first_child), Err(NodeDoesNotExist));
+    }
+
+    #[test]
+    fn iter_edges() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        let great_grandchild = sg.attach(grandchild, "First Great-Grandchild").unwrap();
+        let second_grandchild = sg.attach(first_child, "Second Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+
+        assert_eq!(
+            BTreeSet::from_iter(sg.iter_edges()),
+            BTreeSet::from([
+                (NodeIndex::Root, first_child),
+                (first_child, grandchild),
+                (grandchild, great_grandchild),
+                (first_child, second_grandchild),
+                (NodeIndex::Root, second_child),
+            ])
+        );
+        assert_eq!(sg.iter_edges().count(), sg.len());
+        assert!(SceneGraph::new("Root").iter_edges().next().is_none());
+    }
+
+    #[test]
+    fn collect_indices_dfs() {
+        let mut sg = SceneGraph::new(0);
+        let first_child = sg.attach_at_root(1);
+        let grandchild = sg.attach(first_child, 2).unwrap();
+        sg.attach(grandchild, 3).unwrap();
+        sg.attach(first_child, 4).unwrap();
+        sg.attach_at_root(5);
+
+        let indices = sg.collect_indices_dfs();
+        assert_eq!(indices, Vec::from_iter(sg.iter_with_indices().map(|(_, idx, _)| idx)));
+        assert_eq!(indices.len(), sg.len());
+        assert!(SceneGraph::new(0).collect_indices_dfs().is_empty());
+
+        // change the graph while walking the snapshot
+        for idx in indices.iter() {
+            let Some(node) = sg.get_mut(*idx) else { continue };
+            node.value *= 10;
+
+            if node.value == 20 {
+                sg.remove(*idx).unwrap();
+            }
+        }
+
+        assert_eq!(Vec::from_iter(sg.iter().map(|(_, value)| *value)), vec![10, 40, 50]);
+        assert!(!sg.contains(indices[2]));
+        assert_eq!(sg[indices[3]], 40);
+    }
+
+    #[test]
+    fn detach_into_vec() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach(grandchild, "First Great-Grandchild").unwrap();
+        sg.attach(first_child, "Second Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+
+        let mut expected_sg = sg.clone();
+        let expected = Vec::from_iter(expected_sg.iter_detach(first_child).unwrap());
+
+        let detached = sg.detach_into_vec(first_child).unwrap();
+        assert_eq!(detached, expected);
+        assert_eq!(
+            Vec::from_iter(detached.iter().map(|v| v.node_value)),
+            vec!["First Grandchild", "First Great-Grandchild", "Second Grandchild"]
+        );
+        assert_eq!(detached[1].parent_idx, grandchild);
+
+        assert_eq!(sg, expected_sg);
+        assert_eq!(get_values(&sg), vec!["First Child", "Second Child"]);
+        assert!(!sg.get(first_child).unwrap().has_children());
+        assert!(!sg.contains(grandchild));
+        assert!(sg.detach_into_vec(second_child).unwrap().is_empty());
+
+        sg.remove(first_child).unwrap();
+        assert!(sg.detach_into_vec(first_child).is_err());
+    }
+
+    #[test]
+    fn swap_siblings() {
+        let mut sg = SceneGraph::new("Root");
+        let a = sg.attach_at_root("A");
+        let b = sg.attach_at_root("B");
+        let c = sg.attach_at_root("C");
+        let d = sg.attach_at_root("D");
+        let grandchild = sg.attach(b, "B's Child").unwrap();
+
+        // adjacent
+        sg.swap_siblings(a, b).unwrap();
+        assert_eq!(get_values(&sg), vec!["B", "B's Child", "A", "C", "D"]);
+        assert_eq!(sg.children_bounds(NodeIndex::Root), Some((b, d)));
+
+        // first and last
+        sg.swap_siblings(d, b).unwrap();
+        assert_eq!(get_values(&sg), vec!["D", "A", "C", "B", "B's Child"]);
+        assert_eq!(sg.children_bounds(NodeIndex::Root), Some((d, b)));
+
+        // in the middle
+        sg.swap_siblings(a, c).unwrap();
+        assert_eq!(get_values(&sg), vec!["D", "C", "A", "B", "B's Child"]);
+        assert_eq!(sg.get(d).unwrap().previous_sibling(), None);
+        assert_eq!(sg.get(c).unwrap().previous_sibling(), Some(d));
+        assert_eq!(sg.get(a).unwrap().previous_sibling(), Some(c));
+        assert_eq!(sg.get(b).unwrap().previous_sibling(), Some(a));
+        assert_eq!(sg.get(b).unwrap().next_sibling(), None);
+
+        sg.swap_siblings(a, a).unwrap();
+        assert_eq!(get_values(&sg), vec!["D", "C", "A", "B", "B's Child"]);
+        assert_eq!(sg.num_children(NodeIndex::Root), 4);
+
+        assert_eq!(sg.swap_siblings(a, grandchild), Err(SiblingError::NotSiblings));
+        assert_eq!(sg.swap_siblings(NodeIndex::Root, a), Err(SiblingError::NodeDoesNotExist));
+        sg.remove(d).unwrap();
+        assert_eq!(sg.swap_siblings(a, d), Err(SiblingError::NodeDoesNotExist));
+    }
+
+    #[test]
+    fn move_node_before_and_after() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let first_grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        let second_grandchild = sg.attach(first_child, "Second Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+        let moving = sg.attach(second_child, "Moving").unwrap();
+        sg.attach(moving, "Moving's Child").unwrap();
+
+        // to the front of a new parent
+        sg.move_node_before(moving, first_grandchild).unwrap();
+        assert_eq!(sg.parent(moving), Some(first_child));
+        assert_eq!(sg.first_child(first_child), Some(moving));
+        assert_eq!(sg.num_children(first_child), 3);
+        assert!(!sg.get(second_child).unwrap().has_children());
+        assert_eq!(
+            get_values(&sg),
+            vec![
+                "First Child",
+                "Moving",
+                "Moving's Child",
+                "First Grandchild",
+                "Second Grandchild",
+                "Second Child"
+            ]
+        );
+
+        // within the same parent
+        sg.move_node_after(moving, first_grandchild).unwrap();
+        assert_eq!(sg.first_child(first_child), Some(first_grandchild));
+        assert_eq!(sg.get(second_grandchild).unwrap().previous_sibling(), Some(moving));
+        sg.move_node_after(moving, second_grandchild).unwrap();
+        assert_eq!(sg.last_child(first_child), Some(moving));
+        assert_eq!(sg.num_children(first_child), 3);
+
+        // up to the root
+        sg.move_node_after(moving, first_child).unwrap();
+        assert_eq!(sg.depth(moving), Some(1));
+        assert_eq!(sg.get(second_child).unwrap().previous_sibling(), Some(moving));
+        assert_eq!(
+            get_values(&sg),
+            vec![
+                "First Child",
+                "First Grandchild",
+                "Second Grandchild",
+                "Moving",
+                "Moving's Child",
+                "Second Child"
+            ]
+        );
+
+        sg.move_node_before(moving, moving).unwrap();
+        assert_eq!(sg.num_children(NodeIndex::Root), 3);
+
+        assert_eq!(
+            sg.move_node_after(first_child, first_grandchild),
+            Err(MoveError::WouldCreateCycle)
+        );
+        assert_eq!(
+            sg.move_node_after(NodeIndex::Root, first_child),
+            Err(MoveError::NodeDoesNotExist)
+        );
+        assert_eq!(
+            sg.move_node_before(first_child, NodeIndex::Root),
+            Err(MoveError::NodeDoesNotExist)
+        );
+        sg.remove(second_child).unwrap();
+        assert_eq!(
+            sg.move_node_before(moving, second_child),
+            Err(MoveError::NodeDoesNotExist)
+        );
+    }
+
+    #[test]
+    fn swap_nodes() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let a = sg.attach(first_child, "A").unwrap();
+        let a_child = sg.attach(a, "A's Child").unwrap();
+        sg.attach(first_child, "After A").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+        sg.attach(second_child, "Before B").unwrap();
+        let b = sg.attach(second_child, "B").unwrap();
+        sg.attach(b, "B's First Child").unwrap();
+        sg.attach(b, "B's Second Child").unwrap();
+
+        // cousins
+        sg.swap_nodes(a, b).unwrap();
+        assert_eq!(
+            get_values(&sg),
+            vec![
+                "First Child",
+                "B",
+                "B's First Child",
+                "B's Second Child",
+                "After A",
+                "Second Child",
+                "Before B",
+                "A",
+                "A's Child"
+            ]
+        );
+        assert_eq!(sg.parent(a), Some(second_child));
+        assert_eq!(sg.parent(b), Some(first_child));
+        assert_eq!(sg.parent(a_child), Some(a));
+        assert_eq!(sg.num_children(first_child), 2);
+        assert_eq!(sg.num_children(second_child), 2);
+        assert_eq!(sg.validate(), Ok(()));
+
+        // different depths, and back again
+        sg.swap_nodes(a, first_child).unwrap();
+        assert_eq!(sg.depth(a), Some(1));
+        assert_eq!(sg.depth(first_child), Some(2));
+        assert_eq!(sg.first_child(NodeIndex::Root), Some(a));
+        assert_eq!(sg.validate(), Ok(()));
+        sg.swap_nodes(first_child, a).unwrap();
+        sg.swap_nodes(b, a).unwrap();
+        assert_eq!(sg.parent(a), Some(first_child));
+        assert_eq!(sg.first_child(first_child), Some(a));
+        assert_eq!(sg.last_child(second_child), Some(b));
+
+        // siblings
+        sg.swap_nodes(first_child, second_child).unwrap();
+        assert_eq!(sg.children_bounds(NodeIndex::Root), Some((second_child, first_child)));
+        assert_eq!(sg.validate(), Ok(()));
+
+        sg.swap_nodes(a, a).unwrap();
+        assert_eq!(sg.swap_nodes(first_child, a_child), Err(SwapError::AncestorOfOther));
+        assert_eq!(sg.swap_nodes(a_child, first_child), Err(SwapError::AncestorOfOther));
+        assert_eq!(sg.swap_nodes(NodeIndex::Root, a), Err(SwapError::NodeDoesNotExist));
+        sg.remove(b).unwrap();
+        assert_eq!(sg.swap_nodes(a, b), Err(SwapError::NodeDoesNotExist));
+    }
+
+    #[test]
+    fn promote() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        sg.attach(first_child, "First Grandchild").unwrap();
+        let second_grandchild = sg.attach(first_child, "Second Grandchild").unwrap();
+        let great_grandchild = sg.attach(second_grandchild, "Great-Grandchild").unwrap();
+        sg.attach(first_child, "Third Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+
+        sg.promote(second_grandchild).unwrap();
+        assert_eq!(sg.parent(second_grandchild), Some(NodeIndex::Root));
+        assert_eq!(sg.num_children(first_child), 2);
+        assert_eq!(sg.num_children(NodeIndex::Root), 3);
+        assert_eq!(
+            Vec::from_iter(sg.iter_with_depth().map(|(depth, value)| (depth, *value))),
+            vec![
+                (1, "First Child"),
+                (2, "First Grandchild"),
+                (2, "Third Grandchild"),
+                (1, "Second Grandchild"),
+                (2, "Great-Grandchild"),
+                (1, "Second Child"),
+            ]
+        );
+        assert_eq!(sg.get(second_child).unwrap().previous_sibling(), Some(second_grandchild));
+
+        // already at the top
+        sg.promote(second_grandchild).unwrap();
+        assert_eq!(sg.parent(second_grandchild), Some(NodeIndex::Root));
+        assert_eq!(sg.num_children(NodeIndex::Root), 3);
+
+        sg.promote(great_grandchild).unwrap();
+        assert_eq!(sg.depth(great_grandchild), Some(1));
+        assert_eq!(sg.last_child(NodeIndex::Root), Some(second_child));
+        assert!(!sg.get(second_grandchild).unwrap().has_children());
+
+        assert!(sg.promote(NodeIndex::Root).is_err());
+        sg.remove(first_child).unwrap();
+        assert!(sg.promote(first_child).is_err());
+    }
+
+    #[test]
+    fn iter_leaves() {
+        let mut sg = SceneGraph::new("Root");
+        assert!(sg.iter_leaves().next().is_none());
+
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        let great_grandchild = sg.attach(grandchild, "First Great-Grandchild").unwrap();
+        let second_grandchild = sg.attach(first_child, "Second Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+
+        assert_eq!(
+            Vec::from_iter(sg.iter_leaves()),
+            vec![
+                (great_grandchild, &"First Great-Grandchild"),
+                (second_grandchild, &"Second Grandchild"),
+                (second_child, &"Second Child")
+            ]
+        );
+        assert_eq!(sg.iter_leaves().count(), sg.count_leaves());
+    }
+
+    #[test]
+    fn subtree_size() {
+        let mut sg = SceneGraph::new("Root");
+        assert_eq!(sg.subtree_size(NodeIndex::Root), Some(1));
+
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        let great_grandchild = sg.attach(grandchild, "First Great-Grandchild").unwrap();
+        sg.attach(first_child, "Second Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+
+        assert_eq!(sg.subtree_size(NodeIndex::Root), Some(6));
+        assert_eq!(sg.subtree_size(first_child), Some(4));
+        assert_eq!(sg.subtree_size(grandchild), Some(2));
+        assert_eq!(sg.subtree_size(great_grandchild), Some(1));
+        assert_eq!(sg.subtree_size(second_child), Some(1));
+
+        sg.remove(grandchild).unwrap();
+        assert_eq!(sg.subtree_size(grandchild), None);
+        assert_eq!(sg.subtree_size(first_child), Some(2));
+    }
+
+    #[test]
+    fn count_leaves() {
+        let mut sg = SceneGraph::new("Root");
+        assert_eq!(sg.count_leaves(), 0);
+
+        let first_child = sg.attach_at_root("First Child");
+        assert_eq!(sg.count_leaves(), 1);
+
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach(grandchild, "First Great-Grandchild").unwrap();
+        sg.attach(first_child, "Second Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+        sg.attach_at_root("Third Child");
+        assert_eq!(sg.count_leaves(), 4);
+
+        sg.attach(second_child, "Third Grandchild").unwrap();
+        assert_eq!(sg.count_leaves(), 4);
+
+        sg.remove(first_child).unwrap();
+        assert_eq!(sg.count_leaves(), 2);
+    }
+
+    #[test]
+    fn num_children() {
+        let mut sg = SceneGraph::new("Root");
+        assert_eq!(sg.num_children(NodeIndex::Root), 0);
+
+        let first_child = sg.attach_at_root("First Child");
+        let second_child = sg.attach_at_root("Second Child");
+        let third_child = sg.attach_at_root("Third Child");
+        sg.attach(first_child, "First Grandchild").unwrap();
+        let second_grandchild = sg.attach(first_child, "Second Grandchild").unwrap();
+
+        assert_eq!(sg.num_children(NodeIndex::Root), 3);
+        assert_eq!(sg.num_children(first_child), 2);
+        assert_eq!(sg.num_children(second_child), 0);
+
+        sg.move_node(second_grandchild, second_child).unwrap();
+        assert_eq!(sg.num_children(first_child), 1);
+        assert_eq!(sg.num_children(second_child), 1);
+
+        sg.move_node(third_child, first_child).unwrap();
+        assert_eq!(sg.num_children(NodeIndex::Root), 2);
+        assert_eq!(sg.num_children(first_child), 2);
+
+        let detached = sg.detach(first_child).unwrap();
+        assert_eq!(sg.num_children(NodeIndex::Root), 1);
+        assert_eq!(detached.num_children(NodeIndex::Root), 2);
+        assert_eq!(sg.num_children(first_child), 0);
+
+        sg.remove(second_grandchild).unwrap();
+        assert_eq!(sg.num_children(second_child), 0);
+
+        sg.remove(second_child).unwrap();
+        assert_eq!(sg.num_children(NodeIndex::Root), 0);
+    }
+
+    #[test]
+    fn move_node_into_own_subtree() {
+        let mut sg = SceneGraph::new("Root");
+        let fg = sg.attach(NodeIndex::Root, "First Child").unwrap();
+        let first_grandchild = sg.attach(fg, "First Grandchild").unwrap();
+        let great_grandchild = sg.attach(first_grandchild, "First Great-Grandchild").unwrap();
+        sg.attach(fg, "Second Grandchild").unwrap();
+        sg.attach_at_root("Second Child");
+
+        let before = Vec::from_iter(sg.iter().map(|(parent, value)| (*parent, *value)));
+
+        assert_eq!(sg.move_node(fg, great_grandchild), Err(MoveError::WouldCreateCycle));
+        assert_eq!(sg.move_node(fg, first_grandchild), Err(MoveError::WouldCreateCycle));
+        assert_eq!(sg.move_node(fg, fg), Err(MoveError::WouldCreateCycle));
+        assert_eq!(sg.move_node(NodeIndex::Root, fg), Err(MoveError::NodeDoesNotExist));
+
+        assert_eq!(Vec::from_iter(sg.iter().map(|(parent, value)| (*parent, *value))), before);
+        assert_eq!(sg.parent(fg), Some(NodeIndex::Root));
+    }
+
+    #[test]
+    fn move_node_into_empty_parent() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let second_child = sg.attach_at_root("Second Child");
+
+        sg.move_node(second_child, first_child).unwrap();
+
+        assert_eq!(sg.get(second_child).unwrap().previous_sibling(), None);
+        assert_eq!(sg.get(first_child).unwrap().next_sibling(), None);
+        assert_eq!(
+            Vec::from_iter(sg.iter().map(|(_parent, value)| value).cloned()),
+            vec!["First Child", "Second Child"]
+        );
+    }
+
+    #[test]
+    fn remove() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        sg.attach(first_child, "First Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+
+        assert_eq!(sg.remove(NodeIndex::Root), Err(CannotRemoveRoot));
+        assert_eq!(sg.len(), 3);
+
+        assert_eq!(sg.remove(first_child), Ok(()));
+        assert_eq!(sg.len(), 1);
+        assert!(!sg.contains(first_child));
+        assert_eq!(get_values(&sg), vec!["Second Child"]);
+
+        // removing it twice is harmless
+        assert_eq!(sg.remove(first_child), Ok(()));
+        assert!(sg.contains(second_child));
+    }
+
+    #[test]
+    fn attach_many() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let existing = sg.attach(first_child, "Existing").unwrap();
+        sg.attach_at_root("Second Child");
+
+        let new_indices = sg.attach_many(first_child, ["A", "B", "C"]).unwrap();
+        assert_eq!(Vec::from_iter(new_indices.iter().map(|idx| sg[*idx])), vec!["A", "B", "C"]);
+        assert_eq!(get_values(&sg), vec!["First Child", "Existing", "A", "B", "C", "Second Child"]);
+        assert_eq!(sg.num_children(first_child), 4);
+        assert_eq!(sg.first_child(first_child), Some(existing));
+        assert_eq!(sg.last_child(first_child), Some(new_indices[2]));
+        assert_eq!(sg.get(new_indices[0]).unwrap().previous_sibling(), Some(existing));
+        assert_eq!(sg.validate(), Ok(()));
+
+        // into a node without children, and then attaching normally after the run
+        let new_indices = sg.attach_many(existing, ["D", "E"]).unwrap();
+        let after = sg.attach(existing, "F").unwrap();
+        assert_eq!(sg.first_child(existing), Some(new_indices[0]));
+        assert_eq!(sg.get(after).unwrap().previous_sibling(), Some(new_indices[1]));
+        assert_eq!(sg.num_children(existing), 3);
+        assert_eq!(sg.validate(), Ok(()));
+
+        assert_eq!(sg.attach_many(NodeIndex::Root, []), Ok(vec![]));
+        assert_eq!(sg.num_children(NodeIndex::Root), 2);
+
+        sg.remove(existing).unwrap();
+        assert_eq!(sg.attach_many(existing, ["G"]), Err(ParentNodeNotFound));
+        assert_eq!(sg.len(), 5);
+    }
+
+    #[test]
+    fn attach_many_panicking_iterator() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        sg.attach(first_child, "Existing").unwrap();
+
+        let values = ["A", "B", "C"].into_iter().inspect(|value| {
+            if *value == "C" {
+                panic!("ran out of values");
+            }
+        });
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| sg.attach_many(first_child, values)));
+
+        // everything before the panic made it in, and the parent knows about all of it
+        assert!(result.is_err());
+        assert_eq!(get_values(&sg), vec!["First Child", "Existing", "A", "B"]);
+        assert_eq!(sg.num_children(first_child), 3);
+        assert_eq!(sg.validate(), Ok(()));
+    }
+
+    #[test]
+    fn get_value() {
+        let mut sg = SceneGraph::new(0);
+        let first_child = sg.attach_at_root(1);
+
+        assert_eq!(sg.get_value(NodeIndex::Root), Some(&0));
+        assert_eq!(sg.get_value(first_child), Some(&1));
+        assert!(sg.get(NodeIndex::Root).is_none());
+
+        *sg.get_value_mut(NodeIndex::Root).unwrap() = 10;
+        *sg.get_value_mut(first_child).unwrap() += 10;
+        assert_eq!(*sg.root(), 10);
+        assert_eq!(sg[first_child], 11);
+
+        sg.remove(first_child).unwrap();
+        assert_eq!(sg.get_value(first_child), None);
+        assert_eq!(sg.get_value_mut(first_child), None);
+    }
+
+    #[test]
+    fn set_root() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        sg.attach(first_child, "First Grandchild").unwrap();
+
+        assert_eq!(sg.set_root("New Root"), "Root");
+        assert_eq!(*sg.root(), "New Root");
+        assert_eq!(get_values(&sg), vec!["First Child", "First Grandchild"]);
+        assert_eq!(sg.parent(first_child), Some(NodeIndex::Root));
+        assert_eq!(sg.iter().next(), Some((&"New Root", &"First Child")));
+    }
+
+    #[test]
+    fn root_helpers() {
+        let mut sg = SceneGraph::new("Root");
+        assert_eq!(sg.root_index(), NodeIndex::Root);
+        assert!(!sg.root_has_children());
+        assert!(sg.root_children_iter().next().is_none());
+        assert!(sg.get(sg.root_index()).is_none());
+        assert_eq!(sg.parent(sg.root_index()), None);
+
+        let first_child = sg.attach_at_root("First Child");
+        sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach_at_root("Second Child");
+
+        assert!(sg.root_has_children());
+        assert_eq!(
+            Vec::from_iter(sg.root_children_iter().copied()),
+            vec!["First Child", "Second Child"]
+        );
+        assert_eq!(sg.parent(first_child), Some(sg.root_index()));
+    }
+
+    #[test]
+    fn replace_subtree() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let before = sg.attach(first_child, "Before").unwrap();
+        let replaced = sg.attach(first_child, "Old").unwrap();
+        let old_child = sg.attach(replaced, "Old Child").unwrap();
+        let after = sg.attach(first_child, "After").unwrap();
+        sg.attach_at_root("Second Child");
+
+        let new = scene_graph! {
+            "New" => {
+                "New Child" => { "New Grandchild" },
+                "Second New Child",
+            }
+        };
+
+        let new_idx = sg.replace_subtree(replaced, new).unwrap();
+        assert_eq!(new_idx, replaced);
+        assert_eq!(
+            get_values(&sg),
+            vec![
+                "First Child",
+                "Before",
+                "New",
+                "New Child",
+                "New Grandchild",
+                "Second New Child",
+                "After",
+                "Second Child"
+            ]
+        );
+        assert_eq!(sg.num_children(first_child), 3);
+        assert_eq!(sg.child_at(first_child, 0), Some(before));
+        assert_eq!(sg.child_at(first_child, 1), Some(new_idx));
+        assert_eq!(sg.child_at(first_child, 2), Some(after));
+        assert_eq!(sg.num_children(new_idx), 2);
+        assert!(!sg.contains(old_child));
+        assert_eq!(sg.validate(), Ok(()));
+
+        // an empty graph just replaces the value and drops the children
+        sg.replace_subtree(new_idx, SceneGraph::new("Bare")).unwrap();
+        assert_eq!(get_values(&sg), vec!["First Child", "Before", "Bare", "After", "Second Child"]);
+        assert_eq!(sg.len(), 5);
+
+        assert_eq!(sg.replace_subtree(NodeIndex::Root, SceneGraph::new("Nope")), Err(NodeDoesNotExist));
+        sg.remove(new_idx).unwrap();
+        assert_eq!(sg.replace_subtree(new_idx, SceneGraph::new("Nope")), Err(NodeDoesNotExist));
+    }
+
+    #[test]
+    fn split_off_matches_detach() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach(grandchild, "First Great-Grandchild").unwrap();
+        let second_grandchild = sg.attach(first_child, "Second Grandchild").unwrap();
+        sg.attach(second_grandchild, "Second Great-Grandchild").unwrap();
+        sg.attach(first_child, "Third Grandchild").unwrap();
+        sg.attach_at_root("Second Child");
+
+        let mut detached_sg = sg.clone();
+        let detached = detached_sg.detach(first_child).unwrap();
+        let split = sg.split_off(first_child).unwrap();
+
+        assert_eq!(split, detached);
+        assert_eq!(split.root(), &"First Child");
+        assert_eq!(
+            get_values(&split),
+            vec![
+                "First Grandchild",
+                "First Great-Grandchild",
+                "Second Grandchild",
+                "Second Great-Grandchild",
+                "Third Grandchild"
+            ]
+        );
+        assert_eq!(split.validate(), Ok(()));
+
+        assert_eq!(sg, detached_sg);
+        assert_eq!(get_values(&sg), vec!["Second Child"]);
+        assert!(!sg.contains(grandchild));
+        assert_eq!(sg.validate(), Ok(()));
+
+        assert!(sg.split_off(first_child).is_none());
+        assert!(sg.split_off(NodeIndex::Root).is_none());
+    }
+
+    #[test]
+    fn detach_into() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach(grandchild, "First Great-Grandchild").unwrap();
+        sg.attach(first_child, "Second Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+
+        let mut dest = SceneGraph::new("Other Root");
+        let anchor = dest.attach_at_root("Anchor");
+        dest.attach(anchor, "Anchor Child").unwrap();
+
+        let new_idx = sg.detach_into(first_child, &mut dest, anchor).unwrap();
+
+        assert_eq!(get_values(&sg), vec!["Second Child"]);
+        assert_eq!(sg.len(), 1);
+        assert!(!sg.contains(first_child));
+        assert!(!sg.contains(grandchild));
+        assert_eq!(sg.first_child(NodeIndex::Root), Some(second_child));
+        assert_eq!(sg.validate(), Ok(()));
+
+        assert_eq!(
+            get_values(&dest),
+            vec![
+                "Anchor",
+                "Anchor Child",
+                "First Child",
+                "First Grandchild",
+                "First Great-Grandchild",
+                "Second Grandchild"
+            ]
+        );
+        assert_eq!(dest[new_idx], "First Child");
+        assert_eq!(dest.parent(new_idx), Some(anchor));
+        assert_eq!(dest.num_children(new_idx), 2);
+        assert_eq!(dest.validate(), Ok(()));
+
+        assert_eq!(
+            sg.detach_into(NodeIndex::Root, &mut dest, anchor),
+            Err(TransferError::NodeDoesNotExist)
+        );
+        assert_eq!(sg.detach_into(first_child, &mut dest, anchor), Err(TransferError::NodeDoesNotExist));
+        dest.remove(new_idx).unwrap();
+        assert_eq!(
+            sg.detach_into(second_child, &mut dest, new_idx),
+            Err(TransferError::ParentNodeNotFound)
+        );
+        assert!(sg.contains(second_child));
+    }
+
+    #[test]
+    fn attach_graph_deep() {
+        let mut other = SceneGraph::new("Other Root");
+        let first_child = other.attach_at_root("First Child");
+        let grandchild = other.attach(first_child, "First Grandchild").unwrap();
+        other.attach(grandchild, "First Great-Grandchild").unwrap();
+        other.attach(grandchild, "Second Great-Grandchild").unwrap();
+        other.attach(first_child, "Second Grandchild").unwrap();
+        other.attach_at_root("Second Child");
+
+        let mut sg = SceneGraph::new("Root");
+        let anchor = sg.attach_at_root("Anchor");
+        sg.attach_at_root("Sibling");
+
+        let (new_root, helper_map) = sg.attach_graph(anchor, other).unwrap();
+        assert_eq!(sg.parent(new_root), Some(anchor));
+        assert_eq!(helper_map[&NodeIndex::Root], new_root);
+
+        assert_eq!(
+            Vec::from_iter(sg.iter().map(|(parent, value)| (*parent, *value))),
+            vec![
+                ("Root", "Anchor"),
+                ("Anchor", "Other Root"),
+                ("Other Root", "First Child"),
+                ("First Child", "First Grandchild"),
+                ("First Grandchild", "First Great-Grandchild"),
+                ("First Grandchild", "Second Great-Grandchild"),
+                ("First Child", "Second Grandchild"),
+                ("Other Root", "Second Child"),
+                ("Root", "Sibling"),
+            ]
+        );
+
+        let new_grandchild = helper_map[&grandchild];
+        assert_eq!(sg.get(new_grandchild).unwrap().value, "First Grandchild");
+        assert_eq!(sg.parent(new_grandchild), Some(helper_map[&first_child]));
+        assert_eq!(sg.num_children(new_grandchild), 2);
+    }
+
+    #[test]
+    fn clone_is_independent() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach_at_root("Second Child");
+
+        let mut cloned = sg.clone();
+        assert_eq!(get_values(&cloned), get_values(&sg));
+        assert_eq!(cloned.get(grandchild).unwrap().value, "First Grandchild");
+
+        cloned.get_mut(grandchild).unwrap().value = "Changed Grandchild";
+        cloned.attach(grandchild, "Great-Grandchild").unwrap();
+        cloned.remove(first_child).unwrap();
+        *cloned.root_mut() = "Changed Root";
+
+        assert_eq!(*sg.root(), "Root");
+        assert_eq!(get_values(&sg), vec!["First Child", "First Grandchild", "Second Child"]);
+        assert_eq!(sg.get(grandchild).unwrap().value, "First Grandchild");
+        assert_eq!(get_values(&cloned), vec!["Second Child"]);
+    }
+
+    #[test]
+    fn structural_eq() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach_at_root("Second Child");
+
+        // built in a different order, with different indices, but the same shape
+        let mut other = SceneGraph::new("Root");
+        let filler = other.attach_at_root("Filler");
+        let other_first_child = other.attach_at_root("First Child");
+        other.attach_at_root("Second Child");
+        other.remove(filler).unwrap();
+        other.attach(other_first_child, "First Grandchild").unwrap();
+
+        assert_eq!(sg, other);
+
+        // differing sibling order
+        let mut swapped = SceneGraph::new("Root");
+        swapped.attach_at_root("Second Child");
+        let swapped_first_child = swapped.attach_at_root("First Child");
+        swapped.attach(swapped_first_child, "First Grandchild").unwrap();
+        assert_ne!(sg, swapped);
+
+        // differing shape
+        let mut flat = SceneGraph::new("Root");
+        flat.attach_at_root("First Child");
+        flat.attach_at_root("First Grandchild");
+        flat.attach_at_root("Second Child");
+        assert_ne!(sg, flat);
+
+        // differing root
+        let mut rerooted = sg.clone();
+        *rerooted.root_mut() = "Other Root";
+        assert_ne!(sg, rerooted);
+    }
+
+    #[test]
+    fn eq_unordered() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach(first_child, "Second Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+        sg.attach(second_child, "First Grandchild").unwrap();
+
+        // every sibling list reversed
+        let mut reversed = SceneGraph::new("Root");
+        let second_child = reversed.attach_at_root("Second Child");
+        reversed.attach(second_child, "First Grandchild").unwrap();
+        let first_child = reversed.attach_at_root("First Child");
+        reversed.attach(first_child, "Second Grandchild").unwrap();
+        reversed.attach(first_child, "First Grandchild").unwrap();
+
+        assert_ne!(sg, reversed);
+        assert!(sg.eq_unordered(&reversed));
+        assert!(reversed.eq_unordered(&sg));
+        assert!(sg.eq_unordered(&sg.clone()));
+
+        // the same bag of values, but a grandchild under the wrong parent
+        let mut moved = reversed.clone();
+        let grandchild = moved.first_child(first_child).unwrap();
+        moved.move_node(grandchild, second_child).unwrap();
+        assert!(!sg.eq_unordered(&moved));
+
+        // an extra node
+        let mut extra = reversed.clone();
+        extra.attach(second_child, "Extra").unwrap();
+        assert!(!sg.eq_unordered(&extra));
+        assert!(!extra.eq_unordered(&sg));
+
+        let mut rerooted = reversed.clone();
+        *rerooted.root_mut() = "Other Root";
+        assert!(!sg.eq_unordered(&rerooted));
+    }
+
+    #[test]
+    fn structural_hash() {
+        fn hash_of(sg: &SceneGraph<&'static str>) -> u64 {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            sg.structural_hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach_at_root("Second Child");
+
+        // built back to front, with different indices, but the same shape
+        let mut other = SceneGraph::new("Root");
+        let second_child = other.attach_at_root("Second Child");
+        let other_first_child = other.attach_before(second_child, "First Child").unwrap();
+        other.attach_front(other_first_child, "First Grandchild").unwrap();
+
+        assert_eq!(sg, other);
+        assert_eq!(hash_of(&sg), hash_of(&other));
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        sg.hash(&mut hasher);
+        assert_eq!(hasher.finish(), hash_of(&sg));
+
+        // the same values in the same order, but a different shape
+        let mut flat = SceneGraph::new("Root");
+        flat.attach_at_root("First Child");
+        flat.attach_at_root("First Grandchild");
+        flat.attach_at_root("Second Child");
+        assert_ne!(hash_of(&sg), hash_of(&flat));
+    }
+
+    #[test]
+    fn map() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach_at_root("Second Child");
+
+        let mut visited = vec![];
+        let mapped = sg.map(|value| {
+            visited.push(value);
+            value.len()
+        });
+
+        assert_eq!(visited, vec!["Root", "First Child", "First Grandchild", "Second Child"]);
+        assert_eq!(*mapped.root(), 4);
+        assert_eq!(
+            Vec::from_iter(mapped.iter().map(|(parent, value)| (*parent, *value))),
+            vec![(4, 11), (11, 16), (4, 12)]
+        );
+    }
+
+    #[test]
+    fn try_map() {
+        let mut sg = SceneGraph::new("0");
+        let first_child = sg.attach_at_root("1");
+        sg.attach(first_child, "2").unwrap();
+
+        let mapped = sg.clone().try_map(|value| value.parse::<u32>()).unwrap();
+        assert_eq!(*mapped.root(), 0);
+        assert_eq!(
+            Vec::from_iter(mapped.iter().map(|(_parent, value)| *value)),
+            vec![1, 2]
+        );
+
+        sg.attach_at_root("not a number");
+        assert!(sg.try_map(|value| value.parse::<u32>()).is_err());
+    }
+
+    #[test]
+    fn contains_value() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        sg.attach(first_child, "First Grandchild").unwrap();
+
+        assert!(sg.contains_value(&"First Grandchild"));
+        assert!(sg.contains_value(&"Root"));
+        assert!(!sg.contains_value(&"Second Child"));
+
+        sg.remove(first_child).unwrap();
+        assert!(!sg.contains_value(&"First Grandchild"));
+    }
+
+    #[test]
+    #[should_panic(expected = "has no children")]
+    fn removing_from_a_corrupted_graph_explains_itself() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        sg.root_children = None;
+
+        sg.remove(first_child).unwrap();
+    }
+
+    #[test]
+    fn validate_healthy() {
+        let mut sg = SceneGraph::new("Root");
+        assert_eq!(sg.validate(), Ok(()));
+
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach(grandchild, "First Great-Grandchild").unwrap();
+        let second_grandchild = sg.attach(first_child, "Second Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+        sg.attach_front(second_child, "Front Grandchild").unwrap();
+        assert_eq!(sg.validate(), Ok(()));
+
+        sg.move_node(grandchild, second_child).unwrap();
+        sg.move_node_before(second_grandchild, second_child).unwrap();
+        sg.reverse_children(NodeIndex::Root).unwrap();
+        sg.promote(grandchild).unwrap();
+        sg.remove(first_child).unwrap();
+        assert_eq!(sg.validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_corrupted() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+        let healthy = sg.clone();
+        let (NodeIndex::Branch(first_idx), NodeIndex::Branch(grandchild_idx), NodeIndex::Branch(second_idx)) =
+            (first_child, grandchild, second_child)
+        else {
+            unreachable!()
+        };
+
+        // a parent which is gone
+        let mut broken = healthy.clone();
+        broken.remove(second_child).unwrap();
+        broken.arena[grandchild_idx].parent = second_child;
+        assert_eq!(broken.validate(), Err(ValidationError::DanglingParent(grandchild)));
+
+        // a sibling chain which loops back on itself
+        let mut broken = healthy.clone();
+        broken.arena[second_idx].next_sibling = Some(first_idx);
+        assert_eq!(broken.validate(), Err(ValidationError::VisitedTwice(first_child)));
+
+        // linked under the wrong parent
+        let mut broken = healthy.clone();
+        broken.arena[grandchild_idx].parent = second_child;
+        assert_eq!(broken.validate(), Err(ValidationError::WrongParent(grandchild)));
+
+        // `last` doesn't bound the chain
+        let mut broken = healthy.clone();
+        broken.root_children.as_mut().unwrap().last = first_idx;
+        assert_eq!(broken.validate(), Err(ValidationError::WrongLastChild(NodeIndex::Root)));
+
+        let mut broken = healthy.clone();
+        broken.root_children.as_mut().unwrap().count = 3;
+        assert_eq!(broken.validate(), Err(ValidationError::WrongChildCount(NodeIndex::Root)));
+
+        // dropped out of the chain entirely
+        let mut broken = healthy.clone();
+        broken.arena[first_idx].next_sibling = None;
+        broken.root_children = Some(Children {
+            first: first_idx,
+            last: first_idx,
+            count: 1,
+        });
+        assert_eq!(broken.validate(), Err(ValidationError::Unreachable(second_child)));
+
+        assert_eq!(healthy.validate(), Ok(()));
+    }
+
+    #[test]
+    fn check_links_corrupted() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+        let NodeIndex::Branch(second_idx) = second_child else { unreachable!() };
+        for node_index in [NodeIndex::Root, first_child, grandchild, second_child] {
+            assert_eq!(sg.check_links(node_index), Ok(()));
+        }
+
+        let mut broken = sg.clone();
+        broken.arena[second_idx].parent = first_child;
+        assert_eq!(broken.check_links(first_child), Err(ValidationError::WrongParent(second_child)));
+        assert_eq!(broken.check_links(NodeIndex::Root), Err(ValidationError::WrongParent(second_child)));
+
+        let mut broken = sg.clone();
+        broken.arena[second_idx].last_sibling = None;
+        assert_eq!(broken.check_links(second_child), Err(ValidationError::WrongPreviousSibling(second_child)));
+        assert_eq!(broken.check_links(first_child), Err(ValidationError::WrongPreviousSibling(second_child)));
+    }
+
+    #[test]
+    fn check_acyclic_corrupted() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        let great_grandchild = sg.attach(grandchild, "First Great-Grandchild").unwrap();
+        let NodeIndex::Branch(first_idx) = first_child else { unreachable!() };
+        for node_index in [NodeIndex::Root, first_child, grandchild, great_grandchild] {
+            assert_eq!(sg.check_acyclic(node_index), Ok(()));
+        }
+
+        sg.arena[first_idx].parent = grandchild;
+        assert_eq!(
+            sg.check_acyclic(great_grandchild),
+            Err(ValidationError::VisitedTwice(great_grandchild))
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "cycle above the new parent in move_node")]
+    fn cycle_trips_debug_check() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+        let leaf = sg.attach(second_child, "Leaf").unwrap();
+        let NodeIndex::Branch(first_idx) = first_child else { unreachable!() };
+
+        // the first child and its grandchild are now each other's parents, so without the check,
+        // looking for the moved node above its new parent would never finish
+        sg.arena[first_idx].parent = grandchild;
+        sg.move_node(leaf, grandchild).unwrap();
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "broken links after attach")]
+    fn corruption_trips_debug_check() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let second_child = sg.attach_at_root("Second Child");
+        let NodeIndex::Branch(second_idx) = second_child else { unreachable!() };
+
+        // the last child now thinks it lives somewhere else, which the next sibling to come along
+        // will notice
+        sg.arena[second_idx].parent = first_child;
+        sg.attach_at_root("Third Child");
+    }
+
+    #[test]
+    fn fold() {
+        let mut sg = SceneGraph::new(1);
+        let first_child = sg.attach_at_root(10);
+        sg.attach(first_child, 100).unwrap();
+        sg.attach_at_root(1000);
+
+        assert_eq!(sg.fold(0, |acc, value| acc + value), 1111);
+        assert_eq!(
+            sg.fold(vec![], |mut acc, value| {
+                acc.push(*value);
+                acc
+            }),
+            vec![1, 10, 100, 1000]
+        );
+        assert_eq!(SceneGraph::new(5).fold(0, |acc, value| acc + value), 5);
+    }
+
+    #[test]
+    fn iter_all_out_of_order() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach_at_root("Second Child");
+
+        let all = Vec::from_iter(sg.iter_all_out_of_order());
+        assert_eq!(all[0], (NodeIndex::Root, &"Root"));
+        assert_eq!(all.iter().filter(|(idx, _)| idx.is_root()).count(), 1);
+        assert_eq!(all.len(), sg.len() + 1);
+        assert_eq!(all[1..], Vec::from_iter(sg.iter_out_of_order()));
+
+        assert_eq!(
+            Vec::from_iter(SceneGraph::new("Root").iter_all_out_of_order()),
+            vec![(NodeIndex::Root, &"Root")]
+        );
+    }
+
+    #[test]
+    fn descendants_out_of_order() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        let great_grandchild = sg.attach(grandchild, "First Great-Grandchild").unwrap();
+        let second_grandchild = sg.attach(first_child, "Second Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+        sg.attach(second_child, "Third Grandchild").unwrap();
+
+        let descendants: BTreeSet<NodeIndex> = sg.descendants_out_of_order(first_child).map(|(idx, _)| idx).collect();
+        assert_eq!(descendants, BTreeSet::from([grandchild, great_grandchild, second_grandchild]));
+        assert!(!descendants.contains(&second_child));
+        assert!(!descendants.contains(&first_child));
+
+        let values: BTreeSet<&str> = sg.descendants_out_of_order(grandchild).map(|(_, value)| *value).collect();
+        assert_eq!(values, BTreeSet::from(["First Great-Grandchild"]));
+
+        let everything = BTreeSet::from_iter(sg.descendants_out_of_order(NodeIndex::Root).map(|(idx, _)| idx));
+        assert_eq!(everything, sg.iter_out_of_order().map(|(idx, _)| idx).collect());
+
+        assert_eq!(sg.descendants_out_of_order(great_grandchild).count(), 0);
+        sg.remove(first_child).unwrap();
+        assert_eq!(sg.descendants_out_of_order(first_child).count(), 0);
+    }
+
+    #[test]
+    fn for_each_mut() {
+        // (local position, world position)
+        let mut sg = SceneGraph::new((1, 0));
+        let first_child = sg.attach_at_root((10, 0));
+        let grandchild = sg.attach(first_child, (100, 0)).unwrap();
+        sg.attach(grandchild, (1000, 0)).unwrap();
+        sg.attach(first_child, (200, 0)).unwrap();
+        sg.attach_at_root((20, 0));
+
+        let mut visited = vec![];
+        sg.for_each_mut(|parent, (local, world)| {
+            visited.push(parent.map(|(local, _)| *local));
+            *world = *local + parent.map(|(_, world)| *world).unwrap_or_default();
+        });
+
+        assert_eq!(visited, vec![None, Some(1), Some(10), Some(100), Some(10), Some(1)]);
+        assert_eq!(sg.root().1, 1);
+        assert_eq!(
+            Vec::from_iter(sg.iter().map(|(_, (_, world))| *world)),
+            vec![11, 111, 1111, 211, 21]
+        );
+
+        let mut count = 0;
+        SceneGraph::new(0).for_each_mut(|parent, _| {
+            assert!(parent.is_none());
+            count += 1;
+        });
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn iter_out_of_order_mut() {
+        let mut sg = SceneGraph::new(0);
+        let first_child = sg.attach_at_root(1);
+        sg.attach(first_child, 10).unwrap();
+        sg.attach_at_root(2);
+
+        for (idx, value) in sg.iter_out_of_order_mut() {
+            assert_ne!(idx, NodeIndex::Root);
+            *value += 100;
+        }
+
+        assert_eq!(*sg.root(), 0);
+        assert_eq!(Vec::from_iter(sg.iter().map(|(_, value)| *value)), vec![101, 110, 102]);
+        assert_eq!(sg.iter_out_of_order_mut().count(), sg.len());
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn par_iter_out_of_order() {
+        use rayon::iter::ParallelIterator;
+
+        let mut sg = SceneGraph::new(0);
+        for i in 0..1000 {
+            let child = sg.attach_at_root(i);
+            sg.attach(child, i * 2).unwrap();
+        }
+        sg.remove(sg.first_child(NodeIndex::Root).unwrap()).unwrap();
+
+        let sequential: i32 = sg.iter_out_of_order().map(|(_, v)| *v).sum();
+        let parallel: i32 = sg.par_iter_out_of_order().map(|(_, v)| *v).sum();
+        assert_eq!(parallel, sequential);
+        assert_eq!(sg.par_iter_out_of_order().count(), sg.len());
+        assert!(sg
+            .par_iter_out_of_order()
+            .all(|(idx, value)| sg.get(idx).map(|node| &node.value) == Some(value)));
+
+        // lots of spare capacity, and then none at all
+        let mut sg = SceneGraph::with_capacity(0, 1000);
+        sg.attach_at_root(1);
+        assert_eq!(sg.par_iter_out_of_order().map(|(_, v)| *v).collect::<Vec<_>>(), vec![1]);
+        sg.clear();
+        assert_eq!(sg.par_iter_out_of_order().count(), 0);
+    }
+
+    #[test]
+    fn find() {
+        let mut sg = SceneGraph::new(("Root", 0));
+        let first_child = sg.attach_at_root(("First Child", 1));
+        let grandchild = sg.attach(first_child, ("First Grandchild", 2)).unwrap();
+        let second_child = sg.attach_at_root(("Second Child", 2));
+
+        assert_eq!(sg.find(|(_, id)| *id == 0), Some(NodeIndex::Root));
+        assert_eq!(sg.find(|(_, id)| *id == 1), Some(first_child));
+        assert_eq!(sg.find(|(_, id)| *id == 2), Some(grandchild));
+        assert_eq!(sg.find(|(_, id)| *id == 3), None);
+
+        assert_eq!(sg.find_all(|(_, id)| *id == 2), vec![grandchild, second_child]);
+        assert_eq!(
+            sg.find_all(|_| true),
+            vec![NodeIndex::Root, first_child, grandchild, second_child]
+        );
+        assert!(sg.find_all(|(_, id)| *id == 3).is_empty());
+    }
+
+    #[test]
+    fn ancestry() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        let great_grandchild = sg.attach(grandchild, "First Great-Grandchild").unwrap();
+        let second_grandchild = sg.attach(first_child, "Second Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+
+        // direct and indirect descendants
+        assert!(sg.is_ancestor_of(first_child, grandchild));
+        assert!(sg.is_ancestor_of(first_child, great_grandchild));
+        assert!(sg.is_descendant_of(great_grandchild, first_child));
+        assert!(!sg.is_ancestor_of(grandchild, first_child));
+
+        // siblings
+        assert!(!sg.is_ancestor_of(first_child, second_child));
+        assert!(!sg.is_ancestor_of(second_child, first_child));
+        assert!(!sg.is_ancestor_of(grandchild, second_grandchild));
+        assert!(!sg.is_descendant_of(grandchild, second_grandchild));
+
+        // the root
+        assert!(sg.is_ancestor_of(NodeIndex::Root, great_grandchild));
+        assert!(sg.is_descendant_of(second_child, NodeIndex::Root));
+        assert!(!sg.is_ancestor_of(NodeIndex::Root, NodeIndex::Root));
+        assert!(!sg.is_ancestor_of(first_child, NodeIndex::Root));
+
+        // nodes are not their own ancestor
+        assert!(!sg.is_ancestor_of(grandchild, grandchild));
+
+        sg.remove(grandchild).unwrap();
+        assert!(!sg.is_ancestor_of(first_child, great_grandchild));
+        assert!(!sg.is_ancestor_of(grandchild, great_grandchild));
+    }
+
+    #[test]
+    fn subtree_contains() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        let great_grandchild = sg.attach(grandchild, "First Great-Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+
+        assert!(sg.subtree_contains(first_child, first_child));
+        assert!(sg.subtree_contains(first_child, great_grandchild));
+        assert!(sg.subtree_contains(NodeIndex::Root, NodeIndex::Root));
+        assert!(sg.subtree_contains(NodeIndex::Root, second_child));
+        assert!(!sg.subtree_contains(first_child, second_child));
+        assert!(!sg.subtree_contains(great_grandchild, first_child));
+
+        sg.remove(grandchild).unwrap();
+        assert!(!sg.subtree_contains(grandchild, grandchild));
+        assert!(!sg.subtree_contains(first_child, great_grandchild));
+    }
+
+    #[test]
+    fn common_ancestor() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let grandparent = sg.attach(first_child, "Grandparent").unwrap();
+        let first_parent = sg.attach(grandparent, "First Parent").unwrap();
+        let first_cousin = sg.attach(first_parent, "First Cousin").unwrap();
+        let second_parent = sg.attach(grandparent, "Second Parent").unwrap();
+        let second_cousin = sg.attach(second_parent, "Second Cousin").unwrap();
+        let deep_cousin = sg.attach(second_cousin, "Deep Cousin").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+
+        assert_eq!(sg.common_ancestor(first_cousin, second_cousin), Some(grandparent));
+        assert_eq!(sg.common_ancestor(deep_cousin, first_cousin), Some(grandparent));
+        assert_eq!(sg.common_ancestor(first_cousin, second_child), Some(NodeIndex::Root));
+        assert_eq!(sg.common_ancestor(grandparent, deep_cousin), Some(grandparent));
+        assert_eq!(sg.common_ancestor(first_cousin, first_cousin), Some(first_cousin));
+        assert_eq!(sg.common_ancestor(NodeIndex::Root, first_cousin), Some(NodeIndex::Root));
+
+        sg.remove(second_child).unwrap();
+        assert_eq!(sg.common_ancestor(first_cousin, second_child), None);
+        assert_eq!(sg.common_ancestor(second_child, first_cousin), None);
+    }
+
+    #[test]
+    fn relative_path() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let first_grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        let deep = sg.attach(first_grandchild, "First Great-Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+        let second_grandchild = sg.attach(second_child, "Second Grandchild").unwrap();
+
+        assert_eq!(
+            sg.relative_path(deep, second_grandchild),
+            Some((vec![deep, first_grandchild, first_child], vec![second_child, second_grandchild]))
+        );
+        assert_eq!(
+            sg.relative_path(second_grandchild, deep),
+            Some((vec![second_grandchild, second_child], vec![first_child, first_grandchild, deep]))
+        );
+        assert_eq!(sg.relative_path(first_child, deep), Some((vec![], vec![first_grandchild, deep])));
+        assert_eq!(sg.relative_path(deep, first_child), Some((vec![deep, first_grandchild], vec![])));
+        assert_eq!(sg.relative_path(NodeIndex::Root, first_child), Some((vec![], vec![first_child])));
+        assert_eq!(sg.relative_path(deep, deep), Some((vec![], vec![])));
+
+        sg.remove(second_child).unwrap();
+        assert_eq!(sg.relative_path(deep, second_grandchild), None);
+    }
+
+    #[test]
+    fn path_to_root() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        let great_grandchild = sg.attach(grandchild, "First Great-Grandchild").unwrap();
+
+        assert_eq!(sg.path_to_root(NodeIndex::Root), Some(vec![NodeIndex::Root]));
+        assert_eq!(sg.path_to_root(first_child), Some(vec![first_child, NodeIndex::Root]));
+        assert_eq!(
+            sg.path_to_root(great_grandchild),
+            Some(vec![great_grandchild, grandchild, first_child, NodeIndex::Root])
+        );
+
+        sg.remove(grandchild).unwrap();
+        assert_eq!(sg.path_to_root(great_grandchild), None);
+    }
+
+    #[test]
+    fn child_at() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let second_child = sg.attach_at_root("Second Child");
+        let third_child = sg.attach_at_root("Third Child");
+        let grandchild = sg.attach(second_child, "First Grandchild").unwrap();
+
+        assert_eq!(sg.child_at(NodeIndex::Root, 0), Some(first_child));
+        assert_eq!(sg.child_at(NodeIndex::Root, 1), Some(second_child));
+        assert_eq!(sg.child_at(NodeIndex::Root, 2), Some(third_child));
+        assert_eq!(sg.child_at(NodeIndex::Root, 3), None);
+
+        assert_eq!(sg.child_at(second_child, 0), Some(grandchild));
+        assert_eq!(sg.child_at(second_child, 1), None);
+        assert_eq!(sg.child_at(first_child, 0), None);
+
+        sg.remove(second_child).unwrap();
+        assert_eq!(sg.child_at(second_child, 0), None);
+        assert_eq!(sg.child_at(NodeIndex::Root, 1), Some(third_child));
+    }
+
+    #[test]
+    fn node_index_bits() {
+        assert_eq!(NodeIndex::Root.to_bits(), 0);
+        assert_eq!(NodeIndex::from_bits(0), Some(NodeIndex::Root));
+
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        sg.remove(grandchild).unwrap();
+        let reused = sg.attach(first_child, "Second Grandchild").unwrap();
+
+        for node_index in [first_child, grandchild, reused] {
+            assert_ne!(node_index.to_bits(), 0);
+            assert_eq!(NodeIndex::from_bits(node_index.to_bits()), Some(node_index));
+        }
+        assert_ne!(grandchild.to_bits(), reused.to_bits());
+
+        // a generation of 0 is never valid
+        assert_eq!(NodeIndex::from_bits(1), None);
+    }
+
+    #[test]
+    fn iter_siblings() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        sg.attach(first_child, "First Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+        let third_child = sg.attach_at_root("Third Child");
+        let fourth_child = sg.attach_at_root("Fourth Child");
+
+        assert_eq!(
+            Vec::from_iter(sg.iter_siblings(second_child).unwrap()),
+            vec![
+                (first_child, &"First Child"),
+                (third_child, &"Third Child"),
+                (fourth_child, &"Fourth Child")
+            ]
+        );
+        assert_eq!(sg.iter_siblings(fourth_child).unwrap().count(), 3);
+        assert_eq!(sg.iter_siblings(NodeIndex::Root).unwrap().count(), 0);
+
+        let only_child = sg.first_child(first_child).unwrap();
+        assert_eq!(sg.iter_siblings(only_child).unwrap().count(), 0);
+
+        sg.remove(second_child).unwrap();
+        assert!(sg.iter_siblings(second_child).is_err());
+    }
+
+    #[test]
+    fn sibling_index() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+        let third_child = sg.attach_at_root("Third Child");
+
+        assert_eq!(sg.sibling_index(first_child), Some(0));
+        assert_eq!(sg.sibling_index(second_child), Some(1));
+        assert_eq!(sg.sibling_index(third_child), Some(2));
+        assert_eq!(sg.sibling_index(grandchild), Some(0));
+        assert_eq!(sg.sibling_index(NodeIndex::Root), None);
+
+        for i in 0..3 {
+            let child = sg.child_at(NodeIndex::Root, i).unwrap();
+            assert_eq!(sg.sibling_index(child), Some(i));
+        }
+
+        sg.remove(first_child).unwrap();
+        assert_eq!(sg.sibling_index(first_child), None);
+        assert_eq!(sg.sibling_index(third_child), Some(1));
+    }
+
+    #[test]
+    fn first_and_last_child() {
+        let mut sg = SceneGraph::new("Root");
+        assert_eq!(sg.first_child(NodeIndex::Root), None);
+        assert_eq!(sg.last_child(NodeIndex::Root), None);
+
+        let first_child = sg.attach_at_root("First Child");
+        assert_eq!(sg.first_child(NodeIndex::Root), Some(first_child));
+        assert_eq!(sg.last_child(NodeIndex::Root), Some(first_child));
+
+        let second_child = sg.attach_at_root("Second Child");
+        let grandchild = sg.attach(second_child, "First Grandchild").unwrap();
+        assert_eq!(sg.first_child(NodeIndex::Root), Some(first_child));
+        assert_eq!(sg.last_child(NodeIndex::Root), Some(second_child));
+        assert_eq!(sg.first_child(second_child), Some(grandchild));
+        assert_eq!(sg.last_child(second_child), Some(grandchild));
+        assert_eq!(sg.first_child(first_child), None);
+
+        sg.remove(second_child).unwrap();
+        assert_eq!(sg.first_child(second_child), None);
+        assert_eq!(sg.last_child(NodeIndex::Root), Some(first_child));
+    }
+
+    #[test]
+    fn merge_under_root() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        sg.attach(first_child, "First Grandchild").unwrap();
+
+        let mut other = SceneGraph::new("Other Root");
+        let second_child = other.attach_at_root("Second Child");
+        let second_grandchild = other.attach(second_child, "Second Grandchild").unwrap();
+        other.attach_at_root("Third Child");
+
+        let helper_map = sg.merge_under_root(other);
+        assert_eq!(*sg.root(), "Root");
+        assert_eq!(sg.len(), 5);
+        assert_eq!(sg.num_children(NodeIndex::Root), 3);
+        assert_eq!(
+            get_values(&sg),
+            vec![
+                "First Child",
+                "First Grandchild",
+                "Second Child",
+                "Second Grandchild",
+                "Third Child"
+            ]
+        );
+        assert!(!sg.contains_value(&"Other Root"));
+        assert_eq!(helper_map.len(), 3);
+        assert_eq!(sg[helper_map[&second_grandchild]], "Second Grandchild");
+        assert_eq!(sg.parent(helper_map[&second_grandchild]), Some(helper_map[&second_child]));
+
+        sg.merge_under_root(SceneGraph::new("Empty"));
+        assert_eq!(sg.len(), 5);
+    }
+
+    #[test]
+    fn clone_subtree() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach(grandchild, "First Great-Grandchild").unwrap();
+        sg.attach(first_child, "Second Grandchild").unwrap();
+        sg.attach_at_root("Second Child");
+
+        assert!(sg.clone_subtree(NodeIndex::Root).is_none());
+
+        let original = sg.clone();
+        let copy = sg.clone_subtree(first_child).unwrap();
+        assert_eq!(sg, original);
+        assert_eq!(*copy.root(), "First Child");
+        assert_eq!(
+            get_values(&copy),
+            vec!["First Grandchild", "First Great-Grandchild", "Second Grandchild"]
+        );
+
+        let detached = sg.detach(first_child).unwrap();
+        assert_eq!(copy, detached);
+        assert!(sg.clone_subtree(first_child).is_none());
+    }
+
+    #[test]
+    fn prune_where_childless() {
+        let mut sg = SceneGraph::new("Root");
+        let empty_chain = sg.attach_at_root("Container");
+        let inner = sg.attach(empty_chain, "Container").unwrap();
+        sg.attach(inner, "Container").unwrap();
+        sg.attach(inner, "Container").unwrap();
+
+        let kept = sg.attach_at_root("Container");
+        let half_empty = sg.attach(kept, "Container").unwrap();
+        sg.attach(half_empty, "Container").unwrap();
+        let leaf_bearing = sg.attach(kept, "Container").unwrap();
+        let leaf = sg.attach(leaf_bearing, "Leaf").unwrap();
+
+        let mut visited = 0;
+        sg.prune_where_childless(|value| {
+            visited += 1;
+            *value == "Container"
+        });
+
+        assert_eq!(get_values(&sg), vec!["Container", "Container", "Leaf"]);
+        assert!(!sg.contains(empty_chain));
+        assert!(!sg.contains(half_empty));
+        assert_eq!(sg.parent(leaf), Some(leaf_bearing));
+        assert_eq!(sg.parent(leaf_bearing), Some(kept));
+        assert_eq!(sg.validate(), Ok(()));
+        // only the nodes which were childless at the time were checked
+        assert_eq!(visited, 7);
+
+        // nothing but the root is left once the leaf counts too
+        sg.prune_where_childless(|_| true);
+        assert!(sg.is_empty());
+        assert_eq!(*sg.root(), "Root");
+    }
+
+    #[test]
+    fn trim_to_depth() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        let great_grandchild = sg.attach(grandchild, "First Great-Grandchild").unwrap();
+        sg.attach(great_grandchild, "First Great-Great-Grandchild").unwrap();
+        sg.attach(first_child, "Second Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+        sg.attach(second_child, "Third Grandchild").unwrap();
+        assert_eq!(sg.height(), 4);
+
+        let mut untouched = sg.clone();
+        untouched.trim_to_depth(10);
+        assert_eq!(untouched, sg);
+
+        sg.trim_to_depth(2);
+        assert!(sg.height() <= 2);
+        assert_eq!(
+            get_values(&sg),
+            vec!["First Child", "First Grandchild", "Second Grandchild", "Second Child", "Third Grandchild"]
+        );
+        assert!(!sg.get(grandchild).unwrap().has_children());
+        assert!(!sg.contains(great_grandchild));
+        assert_eq!(sg.len(), 5);
+        assert_eq!(sg.validate(), Ok(()));
+
+        sg.trim_to_depth(1);
+        assert_eq!(sg.height(), 1);
+        assert_eq!(get_values(&sg), vec!["First Child", "Second Child"]);
+
+        sg.trim_to_depth(0);
+        assert_eq!(sg.height(), 0);
+        assert!(sg.is_empty());
+        assert_eq!(sg.len(), 0);
+    }
+
+    #[test]
+    fn retain() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let pruned_child = sg.attach_at_root("Pruned Child");
+        let pruned_grandchild = sg.attach(pruned_child, "First Grandchild").unwrap();
+        let pruned_great_grandchild = sg.attach(pruned_grandchild, "First Great-Grandchild").unwrap();
+        let third_child = sg.attach_at_root("Third Child");
+        sg.attach(third_child, "Pruned Grandchild").unwrap();
+        let second_grandchild = sg.attach(third_child, "Second Grandchild").unwrap();
+
+        let mut visited = vec![];
+        sg.retain(|value| {
+            visited.push(*value);
+            !value.starts_with("Pruned")
+        });
+
+        assert_eq!(
+            visited,
+            vec![
+                "First Child",
+                "Pruned Child",
+                "Third Child",
+                "Pruned Grandchild",
+                "Second Grandchild"
+            ]
+        );
+        assert_eq!(
+            get_values(&sg),
+            vec!["First Child", "Third Child", "Second Grandchild"]
+        );
+        assert_eq!(sg.len(), 3);
+
+        assert!(!sg.contains(pruned_child));
+        assert!(!sg.contains(pruned_grandchild));
+        assert!(!sg.contains(pruned_great_grandchild));
+
+        assert_eq!(sg.num_children(NodeIndex::Root), 2);
+        assert_eq!(sg.get(first_child).unwrap().next_sibling(), Some(third_child));
+        assert_eq!(sg.get(third_child).unwrap().previous_sibling(), Some(first_child));
+        assert_eq!(sg.children_bounds(third_child), Some((second_grandchild, second_grandchild)));
+    }
+
+    #[test]
+    fn get_many_mut() {
+        let mut sg = SceneGraph::new(0);
+        let first_child = sg.attach_at_root(1);
+        let grandchild = sg.attach(first_child, 2).unwrap();
+        let second_child = sg.attach_at_root(3);
+
+        let [a, b] = sg.get_many_mut([first_child, second_child]).unwrap();
+        *a += 10;
+        *b = *a;
+        assert_eq!(sg[first_child], 11);
+        assert_eq!(sg[second_child], 11);
+
+        let [a, b, c] = sg.get_many_mut([grandchild, second_child, first_child]).unwrap();
+        core::mem::swap(a, c);
+        *b = 0;
+        assert_eq!(Vec::from_iter(sg.iter().map(|(_, v)| *v)), vec![2, 11, 0]);
+
+        assert!(sg.get_many_mut([first_child, first_child]).is_none());
+        assert!(sg.get_many_mut([first_child, grandchild, first_child]).is_none());
+        assert!(sg.get_many_mut([NodeIndex::Root, first_child]).is_none());
+        sg.remove(grandchild).unwrap();
+        assert!(sg.get_many_mut([first_child, grandchild]).is_none());
+        assert!(sg.get_many_mut::<0>([]).is_some());
+    }
+
+    #[test]
+    fn get_many_mut_never_alias() {
+        let mut sg = SceneGraph::new(0);
+        let first_child = sg.attach_at_root(1);
+        let grandchild = sg.attach(first_child, 2).unwrap();
+        let second_child = sg.attach_at_root(3);
+        let second_grandchild = sg.attach(second_child, 4).unwrap();
+
+        // the duplicate is under a different parent than its neighbors, so it isn't next to its twin
+        // in any traversal
+        assert!(sg.get_many_mut([grandchild, second_grandchild, first_child, grandchild]).is_none());
+
+        // a stale handle to a reused slot isn't a duplicate, it just doesn't exist
+        sg.remove(grandchild).unwrap();
+        let reused = sg.attach(second_child, 5).unwrap();
+        let (NodeIndex::Branch(old), NodeIndex::Branch(new)) = (grandchild, reused) else { unreachable!() };
+        assert_eq!(old.slot(), new.slot());
+        assert!(sg.get_many_mut([grandchild, reused]).is_none());
+
+        // write through every value only after all of them were taken
+        let values = sg.get_many_mut([reused, first_child, second_grandchild, second_child]).unwrap();
+        for value in values {
+            *value *= 10;
+        }
+        assert_eq!(Vec::from_iter(sg.iter().map(|(_, v)| *v)), vec![10, 30, 40, 50]);
+    }
+
+    #[test]
+    fn replace_value() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+
+        assert_eq!(sg.replace_value(first_child, "New Child"), Some("First Child"));
+        assert_eq!(sg[first_child], "New Child");
+        assert_eq!(sg.parent(grandchild), Some(first_child));
+
+        assert_eq!(sg.replace_value(NodeIndex::Root, "New Root"), Some("Root"));
+        assert_eq!(*sg.root(), "New Root");
+
+        sg.remove(grandchild).unwrap();
+        assert_eq!(sg.replace_value(grandchild, "Ghost"), None);
+        assert_eq!(get_values(&sg), vec!["New Child"]);
+    }
+
+    #[test]
+    fn swap_values() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+
+        sg.swap_values(first_child, second_child).unwrap();
+        assert_eq!(sg[first_child], "Second Child");
+        assert_eq!(sg[second_child], "First Child");
+        assert_eq!(sg.parent(grandchild), Some(first_child));
+        assert_eq!(sg.num_children(first_child), 1);
+        assert_eq!(sg.num_children(second_child), 0);
+
+        sg.swap_values(NodeIndex::Root, grandchild).unwrap();
+        assert_eq!(*sg.root(), "First Grandchild");
+        assert_eq!(sg[grandchild], "Root");
+
+        sg.swap_values(first_child, first_child).unwrap();
+        assert_eq!(sg[first_child], "Second Child");
+        sg.swap_values(NodeIndex::Root, NodeIndex::Root).unwrap();
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
-struct Children {
-    first: Index,
-    last: Index,
-}
+        assert_eq!(get_values(&sg), vec!["Second Child", "Root", "First Child"]);
 
-impl<T> std::fmt::Debug for Node<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Node")
-            .field("parent", &self.parent)
-            .field("children", &self.children)
-            .field("next_sibling", &self.next_sibling)
-            .finish()
+        sg.remove(second_child).unwrap();
+        assert!(sg.swap_values(first_child, second_child).is_err());
+        assert!(sg.swap_values(second_child, NodeIndex::Root).is_err());
+        assert!(sg.swap_values(second_child, second_child).is_err());
+        assert_eq!(sg[first_child], "Second Child");
     }
-}
 
-/// A node index into the SceneGraph.
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
-pub enum NodeIndex {
-    /// Signifies that the index corresponds to the root of the graph.
-    Root,
+    #[test]
+    fn first_and_last_leaf() {
+        let mut sg = SceneGraph::new("Root");
+        assert_eq!(sg.first_leaf(NodeIndex::Root), Some(NodeIndex::Root));
 
-    /// Signifies a non-root node.
-    Branch(thunderdome::Index),
-}
+        let first_child = sg.attach_at_root("First Child");
+        let shallow = sg.attach(first_child, "Shallow").unwrap();
+        let middle = sg.attach(first_child, "Middle").unwrap();
+        sg.attach(middle, "Middle Leaf").unwrap();
+        let deep = sg.attach(first_child, "Deep").unwrap();
+        let deeper = sg.attach(deep, "Deeper").unwrap();
+        let deepest = sg.attach(deeper, "Deepest").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
 
-impl NodeIndex {
-    /// Returns `true` if the node index is [`Root`].
-    ///
-    /// [`Root`]: NodeIndex::Root
-    #[must_use]
-    pub fn is_root(&self) -> bool {
-        matches!(self, Self::Root)
+        assert_eq!(sg.first_leaf(first_child), Some(shallow));
+        assert_eq!(sg.last_leaf(first_child), Some(deepest));
+        assert_eq!(sg.first_leaf(NodeIndex::Root), Some(shallow));
+        assert_eq!(sg.last_leaf(NodeIndex::Root), Some(second_child));
+        assert_eq!(sg.first_leaf(deepest), Some(deepest));
+        assert_eq!(sg.last_leaf(shallow), Some(shallow));
+
+        sg.remove(deep).unwrap();
+        assert_eq!(sg.first_leaf(deep), None);
+        assert_eq!(sg.last_leaf(deep), None);
     }
-}
 
-#[derive(Debug, PartialEq, Eq, thiserror::Error)]
-#[error("parent node not found")]
-/// The parent node requested was not found.
-pub struct ParentNodeNotFound;
+    #[test]
+    fn step_in_order() {
+        let mut sg = SceneGraph::new("Root");
+        assert_eq!(sg.next_in_order(NodeIndex::Root), None);
 
-#[derive(Debug, PartialEq, Eq, thiserror::Error)]
-#[error("node does not exist")]
-/// The node does not exist.
-pub struct NodeDoesNotExist;
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach(grandchild, "First Great-Grandchild").unwrap();
+        sg.attach(first_child, "Second Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+        sg.attach(second_child, "Third Grandchild").unwrap();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        let expected = Vec::from_iter(sg.iter_with_indices().map(|(_, idx, _)| idx));
 
-    fn get_values(sg: &SceneGraph<&'static str>) -> Vec<&'static str> {
-        let mut out = vec![];
-        for (_, v) in sg.iter() {
-            out.push(*v);
+        let mut forward = vec![];
+        let mut current = NodeIndex::Root;
+        while let Some(next) = sg.next_in_order(current) {
+            forward.push(next);
+            current = next;
         }
+        assert_eq!(forward, expected);
 
-        out
+        let mut backward = vec![];
+        let mut current = *expected.last().unwrap();
+        while let Some(prev) = sg.prev_in_order(current) {
+            backward.push(current);
+            current = prev;
+        }
+        assert_eq!(current, NodeIndex::Root);
+        backward.reverse();
+        assert_eq!(backward, expected);
+
+        // across subtree boundaries, in both directions
+        assert_eq!(sg.next_in_order(expected[3]), Some(second_child));
+        assert_eq!(sg.prev_in_order(second_child), Some(expected[3]));
+        assert_eq!(sg.prev_in_order(first_child), Some(NodeIndex::Root));
+
+        // no wrapping at either end
+        assert_eq!(sg.next_in_order(expected[5]), None);
+        assert_eq!(sg.prev_in_order(NodeIndex::Root), None);
+
+        sg.remove(second_child).unwrap();
+        assert_eq!(sg.next_in_order(second_child), None);
+        assert_eq!(sg.prev_in_order(second_child), None);
     }
 
     #[test]
-    fn basic_attach() {
+    fn children_bounds() {
         let mut sg = SceneGraph::new("Root");
-        let root_idx = NodeIndex::Root;
-        sg.attach(root_idx, "First Child").unwrap();
-        let second_child = sg.attach(root_idx, "Second Child").unwrap();
-        sg.attach(second_child, "First Grandchild").unwrap();
+        assert_eq!(sg.children_bounds(NodeIndex::Root), None);
 
-        assert_eq!(get_values(&sg), vec!["First Child", "Second Child", "First Grandchild"]);
+        let first_child = sg.attach_at_root("First Child");
+        assert_eq!(sg.children_bounds(NodeIndex::Root), Some((first_child, first_child)));
+        assert_eq!(sg.children_bounds(first_child), None);
+
+        sg.attach_at_root("Second Child");
+        let third_child = sg.attach_at_root("Third Child");
+        assert_eq!(sg.children_bounds(NodeIndex::Root), Some((first_child, third_child)));
+
+        sg.remove(first_child).unwrap();
+        assert_eq!(sg.children_bounds(first_child), None);
     }
 
     #[test]
-    fn attach_internals() {
+    fn attach_front() {
         let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_front(NodeIndex::Root, "First Child").unwrap();
+        sg.attach_at_root("Second Child");
+        let front_child = sg.attach_front(NodeIndex::Root, "Front Child").unwrap();
+        sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach_front(first_child, "Front Grandchild").unwrap();
 
-        assert_eq!(sg.root_children, None);
+        assert_eq!(
+            get_values(&sg),
+            vec![
+                "Front Child",
+                "First Child",
+                "Front Grandchild",
+                "First Grandchild",
+                "Second Child"
+            ]
+        );
+        assert_eq!(sg.first_child(NodeIndex::Root), Some(front_child));
+        assert_eq!(sg.get(front_child).unwrap().next_sibling(), Some(first_child));
+        assert_eq!(sg.get(first_child).unwrap().previous_sibling(), Some(front_child));
+        assert_eq!(sg.num_children(NodeIndex::Root), 3);
 
-        let root_idx = NodeIndex::Root;
+        sg.remove(first_child).unwrap();
+        assert_eq!(sg.attach_front(first_child, "Orphan"), Err(ParentNodeNotFound));
+        assert_eq!(sg.len(), 2);
+    }
 
-        let first_idx = sg.attach(root_idx, "First Child").unwrap();
+    #[test]
+    fn attach_after_and_before() {
+        let mut sg = SceneGraph::new("Root");
+        let first = sg.attach_at_root("First");
+        let last = sg.attach_at_root("Last");
 
-        // assert_eq!(sg.get_root().num_children, 1);
-        assert_eq!(NodeIndex::Branch(sg.root_children.unwrap().first), first_idx);
-        assert_eq!(NodeIndex::Branch(sg.root_children.unwrap().last), first_idx);
+        let before_first = sg.attach_before(first, "Before First").unwrap();
+        let after_first = sg.attach_after(first, "After First").unwrap();
+        let before_last = sg.attach_before(last, "Before Last").unwrap();
+        let after_last = sg.attach_after(last, "After Last").unwrap();
 
-        let second_idx = sg.attach(root_idx, "Second Child").unwrap();
+        assert_eq!(
+            get_values(&sg),
+            vec![
+                "Before First",
+                "First",
+                "After First",
+                "Before Last",
+                "Last",
+                "After Last"
+            ]
+        );
+        assert_eq!(sg.first_child(NodeIndex::Root), Some(before_first));
+        assert_eq!(sg.last_child(NodeIndex::Root), Some(after_last));
+        assert_eq!(sg.num_children(NodeIndex::Root), 6);
 
-        assert_eq!(NodeIndex::Branch(sg.root_children.unwrap().first), first_idx);
-        assert_eq!(NodeIndex::Branch(sg.root_children.unwrap().last), second_idx);
+        assert_eq!(sg.get(after_first).unwrap().previous_sibling(), Some(first));
+        assert_eq!(sg.get(after_first).unwrap().next_sibling(), Some(before_last));
+        assert_eq!(sg.get(before_last).unwrap().previous_sibling(), Some(after_first));
+        assert_eq!(sg.get(last).unwrap().previous_sibling(), Some(before_last));
+        assert_eq!(sg.get(before_first).unwrap().previous_sibling(), None);
+        assert_eq!(sg.get(after_last).unwrap().next_sibling(), None);
+
+        // the new node shares its sibling's parent
+        let grandchild = sg.attach(first, "Grandchild").unwrap();
+        let after_grandchild = sg.attach_after(grandchild, "After Grandchild").unwrap();
+        assert_eq!(sg.parent(after_grandchild), Some(first));
+        assert_eq!(sg.last_child(first), Some(after_grandchild));
+
+        assert_eq!(sg.attach_after(NodeIndex::Root, "Nope"), Err(NodeDoesNotExist));
+        sg.remove(last).unwrap();
+        assert_eq!(sg.attach_before(last, "Nope"), Err(NodeDoesNotExist));
+    }
 
+    #[test]
+    fn reverse_children() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach(first_child, "Second Grandchild").unwrap();
+        sg.attach_at_root("Second Child");
+        let third_child = sg.attach_at_root("Third Child");
+
+        sg.reverse_children(NodeIndex::Root).unwrap();
         assert_eq!(
-            sg.get(first_idx).unwrap().next_sibling.map(NodeIndex::Branch),
-            Some(second_idx)
+            Vec::from_iter(sg.iter_direct_children(NodeIndex::Root).unwrap().cloned()),
+            vec!["Third Child", "Second Child", "First Child"]
         );
-        assert_eq!(sg.get(first_idx).unwrap().last_sibling, None);
+        assert_eq!(
+            Vec::from_iter(sg.iter_direct_children(first_child).unwrap().cloned()),
+            vec!["First Grandchild", "Second Grandchild"]
+        );
+        assert_eq!(sg.first_child(NodeIndex::Root), Some(third_child));
+        assert_eq!(sg.last_child(NodeIndex::Root), Some(first_child));
+        assert_eq!(sg.get(third_child).unwrap().previous_sibling(), None);
+        assert_eq!(sg.get(first_child).unwrap().next_sibling(), None);
 
-        assert_eq!(sg.get(second_idx).unwrap().next_sibling, None);
+        // appending still goes to the end
+        sg.attach_at_root("Fourth Child");
         assert_eq!(
-            sg.get(second_idx).unwrap().last_sibling.map(NodeIndex::Branch),
-            Some(first_idx)
+            Vec::from_iter(sg.iter_direct_children(NodeIndex::Root).unwrap().cloned()),
+            vec!["Third Child", "Second Child", "First Child", "Fourth Child"]
         );
+
+        let leaf = sg.attach(third_child, "Leaf").unwrap();
+        assert_eq!(sg.reverse_children(leaf), Ok(()));
+        sg.remove(leaf).unwrap();
+        assert_eq!(sg.reverse_children(leaf), Err(NodeDoesNotExist));
     }
 
     #[test]
-    fn detach_basic() {
+    fn rotate_children() {
         let mut sg = SceneGraph::new("Root");
         let first_child = sg.attach_at_root("First Child");
-        let second_child = sg.attach_at_root("Second Child");
+        sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach_at_root("Second Child");
         let third_child = sg.attach_at_root("Third Child");
+        let root_children =
+            |sg: &SceneGraph<&'static str>| Vec::from_iter(sg.iter_direct_children(NodeIndex::Root).unwrap().cloned());
 
-        let second_child = sg.detach(second_child).unwrap();
-        assert_eq!(*second_child.root(), "Second Child");
+        sg.rotate_children(NodeIndex::Root, 2).unwrap();
+        assert_eq!(root_children(&sg), vec!["Third Child", "First Child", "Second Child"]);
+        assert_eq!(sg.first_child(NodeIndex::Root), Some(third_child));
+        assert_eq!(sg.iter_direct_children(first_child).unwrap().next(), Some(&"First Grandchild"));
+        assert_eq!(sg.validate(), Ok(()));
 
-        assert_eq!(NodeIndex::Branch(sg.root_children.unwrap().first), first_child);
-        assert_eq!(NodeIndex::Branch(sg.root_children.unwrap().last), third_child);
+        // wraps around past the end
+        sg.rotate_children(NodeIndex::Root, 4).unwrap();
+        assert_eq!(root_children(&sg), vec!["First Child", "Second Child", "Third Child"]);
+        sg.rotate_children(NodeIndex::Root, 3).unwrap();
+        assert_eq!(root_children(&sg), vec!["First Child", "Second Child", "Third Child"]);
+        assert_eq!(sg.last_child(NodeIndex::Root), Some(third_child));
 
-        assert_eq!(sg.get(first_child).unwrap().last_sibling, None);
+        let leaf = sg.attach(third_child, "Leaf").unwrap();
+        assert_eq!(sg.rotate_children(leaf, 1), Ok(()));
+        sg.remove(leaf).unwrap();
+        assert_eq!(sg.rotate_children(leaf, 1), Err(NodeDoesNotExist));
+    }
+
+    #[test]
+    fn insert_between() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        sg.attach(first_child, "First Grandchild").unwrap();
+        let second_grandchild = sg.attach(first_child, "Second Grandchild").unwrap();
+        sg.attach(second_grandchild, "First Great-Grandchild").unwrap();
+        sg.attach(first_child, "Third Grandchild").unwrap();
+
+        let wrapper = sg.insert_between(second_grandchild, "Wrapper").unwrap();
         assert_eq!(
-            sg.get(first_child).unwrap().next_sibling.map(NodeIndex::Branch),
-            Some(third_child)
+            Vec::from_iter(sg.iter().map(|(parent, value)| (*parent, *value))),
+            vec![
+                ("Root", "First Child"),
+                ("First Child", "First Grandchild"),
+                ("First Child", "Wrapper"),
+                ("Wrapper", "Second Grandchild"),
+                ("Second Grandchild", "First Great-Grandchild"),
+                ("First Child", "Third Grandchild"),
+            ]
         );
+        assert_eq!(sg.parent(wrapper), Some(first_child));
+        assert_eq!(sg.parent(second_grandchild), Some(wrapper));
+        assert_eq!(sg.num_children(first_child), 3);
+        assert_eq!(sg.num_children(wrapper), 1);
+        assert_eq!(sg.sibling_index(wrapper), Some(1));
+        assert_eq!(sg.validate(), Ok(()));
+
+        // an only child at the root
+        let mut sg = SceneGraph::new("Root");
+        let only_child = sg.attach_at_root("Only Child");
+        let wrapper = sg.insert_between(only_child, "Wrapper").unwrap();
+        assert_eq!(sg.children_bounds(NodeIndex::Root), Some((wrapper, wrapper)));
+        assert_eq!(sg.children_bounds(wrapper), Some((only_child, only_child)));
+        assert_eq!(sg.validate(), Ok(()));
+
+        assert_eq!(sg.insert_between(NodeIndex::Root, "Nope"), Err(NodeDoesNotExist));
+        sg.remove(wrapper).unwrap();
+        assert_eq!(sg.insert_between(only_child, "Nope"), Err(NodeDoesNotExist));
+        assert!(sg.is_empty());
+    }
+
+    #[test]
+    fn attach_sorted() {
+        let mut sg = SceneGraph::new(0);
+        let parent = sg.attach_at_root(100);
+        sg.attach_at_root(200);
+
+        let mut indices = vec![];
+        for value in [5, 3, 9, 1, 7, 3] {
+            indices.push(sg.attach_sorted(parent, value, |lhs, rhs| lhs.cmp(rhs)).unwrap());
+        }
+        sg.attach(indices[0], 50).unwrap();
 
         assert_eq!(
-            sg.get(third_child).unwrap().last_sibling.map(NodeIndex::Branch),
-            Some(first_child)
+            Vec::from_iter(sg.iter_direct_children(parent).unwrap().copied()),
+            vec![1, 3, 3, 5, 7, 9]
         );
-        assert_eq!(sg.get(third_child).unwrap().next_sibling, None);
+        // equal values go after the ones already there
+        assert_eq!(sg.child_at(parent, 1), Some(indices[1]));
+        assert_eq!(sg.child_at(parent, 2), Some(indices[5]));
+        assert_eq!(sg.parent(indices[3]), Some(parent));
+        assert_eq!(sg.validate(), Ok(()));
 
-        assert_eq!(get_values(&sg), vec!["First Child", "Third Child"]);
+        // the root's children aren't sorted, but the new node still goes before the first greater one
+        sg.attach_sorted(NodeIndex::Root, 150, |lhs, rhs| lhs.cmp(rhs)).unwrap();
+        assert_eq!(
+            Vec::from_iter(sg.iter_direct_children(NodeIndex::Root).unwrap().copied()),
+            vec![100, 150, 200]
+        );
 
-        let g = sg.attach(third_child, "First Grandchild").unwrap();
-        sg.attach(g, "Second Grandchild").unwrap();
-        let g_3 = sg.attach(g, "Third Grandchild").unwrap();
-        sg.attach(g_3, "First Greatgrandchild").unwrap();
+        sg.remove(parent).unwrap();
+        assert_eq!(sg.attach_sorted(parent, 1, |lhs, rhs| lhs.cmp(rhs)), Err(ParentNodeNotFound));
+    }
+
+    #[test]
+    fn sort_children_by() {
+        let mut sg = SceneGraph::new(("Root", 0));
+        let three = sg.attach_at_root(("Three", 3));
+        sg.attach(three, ("Child of Three", 1)).unwrap();
+        sg.attach(three, ("Another Child of Three", 0)).unwrap();
+        let one = sg.attach_at_root(("One", 1));
+        sg.attach_at_root(("Two", 2));
+        let also_one = sg.attach_at_root(("Also One", 1));
+
+        sg.sort_children_by(NodeIndex::Root, |(_, lhs), (_, rhs)| lhs.cmp(rhs))
+            .unwrap();
 
-        let third_child_tree = sg.detach(third_child).unwrap();
-        assert_eq!(get_values(&sg), vec!["First Child"]);
         assert_eq!(
-            get_values(&third_child_tree),
+            Vec::from_iter(sg.iter_direct_children(NodeIndex::Root).unwrap().map(|(name, _)| *name)),
+            vec!["One", "Also One", "Two", "Three"]
+        );
+        assert_eq!(sg.first_child(NodeIndex::Root), Some(one));
+        assert_eq!(sg.last_child(NodeIndex::Root), Some(three));
+        assert_eq!(sg.get(also_one).unwrap().previous_sibling(), Some(one));
+
+        // the subtree travels along, unsorted
+        assert_eq!(
+            Vec::from_iter(sg.iter().map(|(_parent, (name, _))| *name)),
             vec![
-                "First Grandchild",
-                "Second Grandchild",
-                "Third Grandchild",
-                "First Greatgrandchild"
+                "One",
+                "Also One",
+                "Two",
+                "Three",
+                "Child of Three",
+                "Another Child of Three"
             ]
         );
-        assert_eq!(*third_child_tree.root(), "Third Child");
     }
 
     #[test]
-    fn move_node() {
+    fn index() {
         let mut sg = SceneGraph::new("Root");
-        let fg = sg.attach(NodeIndex::Root, "First Child").unwrap();
-        sg.attach(fg, "First Grandchild").unwrap();
-        sg.attach(fg, "Second Grandchild").unwrap();
-        sg.attach(fg, "Third Grandchild").unwrap();
-        let second_child = sg.attach(NodeIndex::Root, "Second Child").unwrap();
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
 
-        assert_eq!(
-            Vec::from_iter(sg.iter_direct_children(fg).unwrap().cloned()),
-            vec!["First Grandchild", "Second Grandchild", "Third Grandchild",]
-        );
+        assert_eq!(sg[first_child], "First Child");
+        assert_eq!(sg[grandchild], "First Grandchild");
 
-        sg.move_node(fg, second_child).unwrap();
+        sg[grandchild] = "Changed Grandchild";
+        assert_eq!(sg.get(grandchild).unwrap().value, "Changed Grandchild");
+    }
 
-        assert_eq!(
-            Vec::from_iter(sg.iter_direct_children(NodeIndex::Root).unwrap().cloned()),
-            vec!["Second Child",]
-        );
+    #[test]
+    #[should_panic(expected = "cannot index the root")]
+    fn index_root_panics() {
+        let sg = SceneGraph::new("Root");
+        let _ = sg[NodeIndex::Root];
+    }
+
+    #[test]
+    #[should_panic(expected = "does not exist")]
+    fn index_removed_panics() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        sg.remove(first_child).unwrap();
+
+        sg[first_child] = "Zombie";
+    }
+
+    #[test]
+    fn scene_graph_macro() {
+        let sg = scene_graph! {
+            "Root" => {
+                "First Child" => {
+                    "First Grandchild" => { "First Great-Grandchild" },
+                    "Second Grandchild",
+                },
+                "Second Child" => {},
+                "Third Child" => { "Third Grandchild" }
+            }
+        };
 
+        let mut manual = SceneGraph::new("Root");
+        let first_child = manual.attach_at_root("First Child");
+        let grandchild = manual.attach(first_child, "First Grandchild").unwrap();
+        manual.attach(grandchild, "First Great-Grandchild").unwrap();
+        manual.attach(first_child, "Second Grandchild").unwrap();
+        manual.attach_at_root("Second Child");
+        let third_child = manual.attach_at_root("Third Child");
+        manual.attach(third_child, "Third Grandchild").unwrap();
+
+        assert_eq!(sg, manual);
+        assert_eq!(sg.len(), 7);
+
+        assert_eq!(scene_graph!("Root"), SceneGraph::new("Root"));
+        assert_eq!(scene_graph!("Root" => {}), SceneGraph::new("Root"));
         assert_eq!(
-            Vec::from_iter(sg.iter_direct_children(fg).unwrap().cloned()),
-            vec!["First Grandchild", "Second Grandchild", "Third Grandchild",]
+            scene_graph!(0 => { 1 + 1, 2 * 2 => { 3 } }),
+            scene_graph!(0 => { 2, 4 => { 3 } })
         );
+    }
+
+    #[test]
+    fn from_root_and_children() {
+        let sg = SceneGraph::from_root_and_children("Root", ["First Child", "Second Child", "Third Child"]);
+        assert_eq!(*sg.root(), "Root");
+        assert_eq!(sg.len(), 3);
+        assert_eq!(sg.num_children(NodeIndex::Root), 3);
+        assert_eq!(get_values(&sg), vec!["First Child", "Second Child", "Third Child"]);
+
+        let sg = SceneGraph::from_root_and_children(0, 1..=1000);
+        assert_eq!(sg.len(), 1000);
+        assert_eq!(sg.height(), 1);
+        assert_eq!(sg.fold(0, |acc, v| acc + v), 500500);
+
+        let sg = SceneGraph::from_root_and_children("Root", []);
+        assert!(sg.is_empty());
+    }
+
+    #[test]
+    fn with_capacity() {
+        let mut sg = SceneGraph::with_capacity("Root", 100);
+        assert_eq!(sg.capacity(), 100);
+
+        let first_child = sg.attach_at_root("First Child");
+        for _ in 0..99 {
+            sg.attach(first_child, "Grandchild").unwrap();
+        }
+        assert_eq!(sg.capacity(), 100);
+
+        // a freed slot is reused without growing, but old handles to it stay dead
+        let last = sg.last_child(first_child).unwrap();
+        sg.remove(last).unwrap();
+        let new_last = sg.attach(first_child, "New Grandchild").unwrap();
+        assert_eq!(sg.capacity(), 100);
+        assert_ne!(last, new_last);
+        assert!(sg.get(last).is_none());
+    }
+
+    #[test]
+    fn default() {
+        let sg = SceneGraph::<i32>::default();
+        assert_eq!(sg.root(), &0);
+        assert!(sg.is_empty());
+
+        #[derive(Default)]
+        struct Scene {
+            graph: SceneGraph<String>,
+        }
+        assert_eq!(Scene::default().graph, SceneGraph::new(String::new()));
+    }
+
+    #[test]
+    fn drain() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach_at_root("Second Child");
+        let capacity = sg.capacity();
 
         assert_eq!(
-            Vec::from_iter(sg.iter_direct_children(second_child).unwrap().cloned()),
-            vec!["First Child",]
+            Vec::from_iter(sg.drain()),
+            vec!["First Child", "First Grandchild", "Second Child"]
         );
+        assert!(sg.is_empty());
+        assert!(!sg.contains(first_child));
+        assert_eq!(*sg.root(), "Root");
+        assert_eq!(sg.capacity(), capacity);
+
+        let first_child = sg.attach_at_root("First Child");
+        sg.attach(first_child, "First Grandchild").unwrap();
+        assert_eq!(sg.drain().next(), Some("First Child"));
+        assert!(sg.is_empty());
+        assert!(sg.iter().next().is_none());
     }
 
     #[test]
@@ -677,3 +5408,75 @@ mod tests {
         assert!(sg.arena.is_empty());
     }
 }
+
+#[cfg(all(test, feature = "log"))]
+mod log_tests {
+    use super::*;
+    use std::{cell::RefCell, sync::Once};
+
+    thread_local! {
+        // tests run on their own threads, so capturing per thread keeps them from seeing each other
+        static EVENTS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    }
+
+    struct CaptureLogger;
+
+    impl log::Log for CaptureLogger {
+        fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+            metadata.target().starts_with("scene_graph")
+        }
+
+        fn log(&self, record: &log::Record<'_>) {
+            if self.enabled(record.metadata()) {
+                EVENTS.with(|events| events.borrow_mut().push(record.args().to_string()));
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    static LOGGER: CaptureLogger = CaptureLogger;
+    static INIT: Once = Once::new();
+
+    fn capture_events(f: impl FnOnce()) -> Vec<String> {
+        INIT.call_once(|| {
+            log::set_logger(&LOGGER).unwrap();
+            log::set_max_level(log::LevelFilter::Debug);
+        });
+
+        EVENTS.with(|events| events.borrow_mut().clear());
+        f();
+        EVENTS.with(|events| events.take())
+    }
+
+    #[test]
+    fn detach_emits_one_event_per_node() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach(grandchild, "First Great-Grandchild").unwrap();
+        sg.attach(first_child, "Second Grandchild").unwrap();
+        sg.attach_at_root("Second Child");
+
+        let events = capture_events(|| {
+            sg.detach(first_child).unwrap();
+        });
+
+        let detached_events = events.iter().filter(|v| v.starts_with("detached")).count();
+        assert_eq!(detached_events, 4);
+    }
+
+    #[test]
+    fn attach_and_move_emit_events() {
+        let mut sg = SceneGraph::new("Root");
+
+        let events = capture_events(|| {
+            let first_child = sg.attach_at_root("First Child");
+            let second_child = sg.attach_at_root("Second Child");
+            sg.move_node(second_child, first_child).unwrap();
+        });
+
+        assert_eq!(events.iter().filter(|v| v.starts_with("attached")).count(), 2);
+        assert_eq!(events.iter().filter(|v| v.starts_with("moved")).count(), 1);
+    }
+}
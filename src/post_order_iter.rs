@@ -0,0 +1,136 @@
+use alloc::vec::Vec;
+
+use crate::{Children, Node, SceneGraph};
+
+/// A post-order depth first iterator over the SceneGraph, which yields every node only after all of
+/// its descendants. See [iter_post_order] for more information.
+///
+/// [iter_post_order]: SceneGraph::iter_post_order
+pub struct SceneGraphPostOrderIter<'a, T> {
+    sg: &'a SceneGraph<T>,
+    stacks: Vec<StackState<'a, T>>,
+}
+
+impl<'a, T> SceneGraphPostOrderIter<'a, T> {
+    pub(crate) fn new(sg: &'a SceneGraph<T>, root_value: &'a T, root_children: Option<&'a Children>) -> Self {
+        let mut stacks = Vec::new();
+        if let Some(first_child) = root_children.map(|v| v.first) {
+            stacks.push(StackState::new(root_value, &sg.arena[first_child]));
+        };
+        SceneGraphPostOrderIter { sg, stacks }
+    }
+}
+
+impl<'a, T> Iterator for SceneGraphPostOrderIter<'a, T> {
+    type Item = (&'a T, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // if we're out of stack frames, we die here
+            let stack_frame = self.stacks.last_mut()?;
+
+            // the first time we see a frame, we descend into its children before yielding it
+            if !stack_frame.visited {
+                stack_frame.visited = true;
+
+                let current_child = stack_frame.current_child;
+                if let Some(first_child) = current_child.children.map(|v| v.first) {
+                    self.stacks
+                        .push(StackState::new(&current_child.value, &self.sg.arena[first_child]));
+                }
+
+                continue;
+            }
+
+            // all of the children are done, so this one is done too.
+            let stack_frame = self.stacks.pop().unwrap();
+            if let Some(next_sibling) = stack_frame.current_child.next_sibling {
+                self.stacks
+                    .push(StackState::new(stack_frame.parent_value, &self.sg.arena[next_sibling]));
+            }
+
+            return Some((stack_frame.parent_value, &stack_frame.current_child.value));
+        }
+    }
+}
+
+#[derive(Debug)]
+struct StackState<'a, T> {
+    parent_value: &'a T,
+    current_child: &'a Node<T>,
+    visited: bool,
+}
+
+impl<'a, T> StackState<'a, T> {
+    fn new(parent: &'a T, first_child: &'a Node<T>) -> Self {
+        Self {
+            parent_value: parent,
+            current_child: first_child,
+            visited: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scene_graph_returns_nothing_on_empty_iteration() {
+        let scene_graph = SceneGraph::new("Root");
+
+        assert!(scene_graph.iter_post_order().next().is_none());
+    }
+
+    #[test]
+    fn post_order_iteration() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        let first_grandchild = sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach(first_grandchild, "First Great-Grandchild").unwrap();
+        sg.attach(first_child, "Second Grandchild").unwrap();
+        let second_child = sg.attach_at_root("Second Child");
+        sg.attach(second_child, "Third Grandchild").unwrap();
+
+        assert_eq!(
+            Vec::from_iter(sg.iter().map(|(_parent, value)| value).cloned()),
+            vec![
+                "First Child",
+                "First Grandchild",
+                "First Great-Grandchild",
+                "Second Grandchild",
+                "Second Child",
+                "Third Grandchild"
+            ]
+        );
+
+        assert_eq!(
+            Vec::from_iter(sg.iter_post_order().map(|(_parent, value)| value).cloned()),
+            vec![
+                "First Great-Grandchild",
+                "First Grandchild",
+                "Second Grandchild",
+                "First Child",
+                "Third Grandchild",
+                "Second Child"
+            ]
+        );
+    }
+
+    #[test]
+    fn post_order_parents() {
+        let mut sg = SceneGraph::new("Root");
+        let first_child = sg.attach_at_root("First Child");
+        sg.attach(first_child, "First Grandchild").unwrap();
+        sg.attach_at_root("Second Child");
+
+        assert_eq!(
+            Vec::from_iter(sg.iter_post_order().map(|(parent, value)| (*parent, *value))),
+            vec![
+                ("First Child", "First Grandchild"),
+                ("Root", "First Child"),
+                ("Root", "Second Child")
+            ]
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use alloc::{vec, vec::Vec};
+use serde::{
+    ser::{SerializeSeq, SerializeStruct},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{Children, NodeIndex, SceneGraph};
+
+/// SceneGraphs are serialized as a nested tree, where the root and every node is written as a
+/// `value` and a list of `children`. `NodeIndex`es are not stable across runs, so they are never
+/// written out.
+impl<T: Serialize> Serialize for SceneGraph<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeNode {
+            sg: self,
+            value: &self.root,
+            children: self.root_children.as_ref(),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// SceneGraphs are deserialized from the nested tree written by `Serialize`, attaching each node in
+/// order. The resulting `NodeIndex`es will not match those of the original graph.
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for SceneGraph<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let root = DeserializeNode::<T>::deserialize(deserializer)?;
+
+        let mut sg = SceneGraph::new(root.value);
+        let mut stacks = vec![(NodeIndex::Root, root.children.into_iter())];
+
+        while let Some((parent, children)) = stacks.last_mut() {
+            match children.next() {
+                Some(child) => {
+                    let new_idx = sg.attach(*parent, child.value).unwrap();
+                    stacks.push((new_idx, child.children.into_iter()));
+                }
+                None => {
+                    stacks.pop();
+                }
+            }
+        }
+
+        Ok(sg)
+    }
+}
+
+struct SerializeNode<'a, T> {
+    sg: &'a SceneGraph<T>,
+    value: &'a T,
+    children: Option<&'a Children>,
+}
+
+impl<'a, T: Serialize> Serialize for SerializeNode<'a, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut node = serializer.serialize_struct("Node", 2)?;
+        node.serialize_field("value", self.value)?;
+        node.serialize_field(
+            "children",
+            &SerializeChildren {
+                sg: self.sg,
+                children: self.children,
+            },
+        )?;
+        node.end()
+    }
+}
+
+struct SerializeChildren<'a, T> {
+    sg: &'a SceneGraph<T>,
+    children: Option<&'a Children>,
+}
+
+impl<'a, T: Serialize> Serialize for SerializeChildren<'a, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.children.map(|v| v.count).unwrap_or_default()))?;
+
+        let mut current_child = self.children.map(|v| v.first);
+        while let Some(idx) = current_child {
+            let node = &self.sg.arena[idx];
+            seq.serialize_element(&SerializeNode {
+                sg: self.sg,
+                value: &node.value,
+                children: node.children.as_ref(),
+            })?;
+
+            current_child = node.next_sibling;
+        }
+
+        seq.end()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Node")]
+struct DeserializeNode<T> {
+    value: T,
+    #[serde(default = "Vec::new")]
+    children: Vec<DeserializeNode<T>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut sg = SceneGraph::new("Root".to_string());
+        let first_child = sg.attach_at_root("First Child".to_string());
+        let grandchild = sg.attach(first_child, "First Grandchild".to_string()).unwrap();
+        sg.attach(grandchild, "First Great-Grandchild".to_string()).unwrap();
+        sg.attach(first_child, "Second Grandchild".to_string()).unwrap();
+        sg.attach_at_root("Second Child".to_string());
+
+        let json = serde_json::to_string(&sg).unwrap();
+        let round_tripped: SceneGraph<String> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(sg, round_tripped);
+        assert_eq!(round_tripped.len(), 5);
+    }
+
+    #[test]
+    fn nested_representation() {
+        let mut sg = SceneGraph::new(0);
+        let first_child = sg.attach_at_root(1);
+        sg.attach(first_child, 2).unwrap();
+
+        assert_eq!(
+            serde_json::to_string(&sg).unwrap(),
+            r#"{"value":0,"children":[{"value":1,"children":[{"value":2,"children":[]}]}]}"#
+        );
+
+        let leafless: SceneGraph<i32> = serde_json::from_str(r#"{"value":0}"#).unwrap();
+        assert!(leafless.is_empty());
+    }
+}